@@ -0,0 +1,310 @@
+use std::prelude::v1::*;
+use crate::*;
+use near_sdk::serde::{Deserialize, Serialize};
+
+pub const MAX_ANALYST_NAME_LENGTH: usize = 64;
+pub const MAX_ANALYST_BIO_LENGTH: usize = 1_000;
+const MAX_ANALYST_LIMIT: u64 = 100;
+/// 3 days for the requester to review a submitted result before it is accepted automatically
+pub const ANALYST_REVIEW_PERIOD_NS: u64 = 3 * 24 * 60 * 60 * 1_000_000_000;
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AnalystProfile {
+    pub account_id: AccountId,
+    pub display_name: String,
+    pub bio: String,
+    /// Fee escrowed from the requester for each case assigned to this analyst
+    pub price: NearToken,
+    pub active: bool,
+    pub completed_cases: u32,
+    pub registered_at: U64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum AssignmentStatus {
+    /// Waiting for the analyst to submit a completed result
+    Assigned,
+    /// Result submitted, waiting for the requester to accept or reject it
+    Submitted,
+    Accepted,
+    Rejected,
+    Refunded,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AnalystAssignment {
+    pub analyst_id: AccountId,
+    pub requester: AccountId,
+    pub fee: NearToken,
+    pub status: AssignmentStatus,
+    pub assigned_at: U64,
+    /// When the analyst submitted a completed result, starting the review period
+    pub submitted_at: Option<U64>,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Lists the caller in the analyst marketplace; the caller pays for the profile storage
+    #[payable]
+    pub fn register_analyst(&mut self, display_name: String, bio: String, price: NearToken) -> AnalystProfile {
+        let account_id = env::predecessor_account_id();
+        require!(self.analysts.get(&account_id).is_none(), "Analyst is already registered");
+        let initial_storage_usage = env::storage_usage();
+
+        let profile = AnalystProfile {
+            account_id: account_id.clone(),
+            display_name,
+            bio,
+            price,
+            active: true,
+            completed_cases: 0,
+            registered_at: U64(env::block_timestamp()),
+        };
+        assert_valid_profile(&profile);
+        self.analysts.insert(&account_id, &profile);
+
+        let storage_cost = env::storage_byte_cost()
+            .saturating_mul(env::storage_usage().saturating_sub(initial_storage_usage) as u128);
+        let deposit = env::attached_deposit();
+        require!(
+            deposit >= storage_cost,
+            format!("Must attach at least {} for storage", storage_cost.exact_amount_display())
+        );
+        if deposit > storage_cost {
+            Promise::new(account_id.clone()).transfer(deposit.saturating_sub(storage_cost));
+        }
+
+        InvestigationEvent::AnalystRegistered {
+            analyst_id: account_id.to_string(),
+            price,
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+
+        profile
+    }
+
+    /// Updates the caller's profile; any storage growth is paid from the attached deposit.
+    /// Inactive analysts cannot be selected for new cases.
+    #[payable]
+    pub fn update_analyst_profile(
+        &mut self,
+        display_name: Option<String>,
+        bio: Option<String>,
+        price: Option<NearToken>,
+        active: Option<bool>,
+    ) -> AnalystProfile {
+        let account_id = env::predecessor_account_id();
+        let mut profile = self.analysts.get(&account_id)
+            .unwrap_or_else(|| env::panic_str("Analyst not registered"));
+        let initial_storage_usage = env::storage_usage();
+
+        if let Some(display_name) = display_name {
+            profile.display_name = display_name;
+        }
+        if let Some(bio) = bio {
+            profile.bio = bio;
+        }
+        if let Some(price) = price {
+            profile.price = price;
+        }
+        if let Some(active) = active {
+            profile.active = active;
+        }
+        assert_valid_profile(&profile);
+        self.analysts.insert(&account_id, &profile);
+
+        let storage_cost = env::storage_byte_cost()
+            .saturating_mul(env::storage_usage().saturating_sub(initial_storage_usage) as u128);
+        let deposit = env::attached_deposit();
+        require!(
+            deposit >= storage_cost,
+            format!("Must attach at least {} for storage", storage_cost.exact_amount_display())
+        );
+        if deposit > storage_cost {
+            Promise::new(account_id).transfer(deposit.saturating_sub(storage_cost));
+        }
+
+        profile
+    }
+
+    /// Accepts the analyst's result, publishes the case as `Completed` and releases the
+    /// escrowed fee to the analyst
+    pub fn accept_analyst_result(&mut self, token_id: TokenId) -> AnalystAssignment {
+        let assignment = self.internal_submitted_assignment(&token_id);
+        require!(
+            env::predecessor_account_id() == assignment.requester,
+            "Only the requester can review the analyst's result"
+        );
+        self.internal_accept_analyst_result(token_id, assignment)
+    }
+
+    /// Lets the analyst collect the escrow once the requester has let the review period
+    /// lapse; the result is accepted as if the requester had done so
+    pub fn claim_analyst_escrow(&mut self, token_id: TokenId) -> AnalystAssignment {
+        let assignment = self.internal_submitted_assignment(&token_id);
+        require!(
+            env::predecessor_account_id() == assignment.analyst_id,
+            "Only the assigned analyst can claim the escrow"
+        );
+        require!(
+            assignment.submitted_at.is_some_and(|submitted_at| {
+                env::block_timestamp() >= submitted_at.0.saturating_add(ANALYST_REVIEW_PERIOD_NS)
+            }),
+            "The review period has not ended yet"
+        );
+        self.internal_accept_analyst_result(token_id, assignment)
+    }
+
+    /// Rejects the analyst's result: the escrowed fee returns to the requester and
+    /// the case is handed back to the regular pipeline queue
+    pub fn reject_analyst_result(&mut self, token_id: TokenId) -> AnalystAssignment {
+        let mut assignment = self.internal_submitted_assignment(&token_id);
+        require!(
+            env::predecessor_account_id() == assignment.requester,
+            "Only the requester can review the analyst's result"
+        );
+        require!(
+            assignment.submitted_at.is_some_and(|submitted_at| {
+                env::block_timestamp() < submitted_at.0.saturating_add(ANALYST_REVIEW_PERIOD_NS)
+            }),
+            "The review period has ended"
+        );
+
+        Promise::new(assignment.requester.clone()).transfer(assignment.fee);
+        assignment.status = AssignmentStatus::Rejected;
+        self.analyst_assignments.insert(&token_id, &assignment);
+        self.internal_set_status(&token_id, InvestigationStatus::Pending);
+
+        InvestigationEvent::AnalystResultRejected {
+            token_id,
+            analyst_id: assignment.analyst_id.to_string(),
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+
+        assignment
+    }
+
+    pub fn get_analyst(&self, account_id: AccountId) -> Option<AnalystProfile> {
+        self.analysts.get(&account_id)
+    }
+
+    pub fn get_analysts(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<AnalystProfile> {
+        let limit = limit.unwrap_or(MAX_ANALYST_LIMIT).min(MAX_ANALYST_LIMIT) as usize;
+        self.analysts
+            .values()
+            .filter(|profile| profile.active)
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit)
+            .collect()
+    }
+
+    pub fn get_analyst_assignment(&self, token_id: TokenId) -> Option<AnalystAssignment> {
+        self.analyst_assignments.get(&token_id)
+    }
+}
+
+impl Contract {
+    /// Escrows the selected analyst's fee for a new case; the caller must have collected it
+    pub(crate) fn internal_assign_analyst(&mut self, token_id: &TokenId, analyst_id: &AccountId, requester: &AccountId) {
+        let profile = self.analysts.get(analyst_id)
+            .unwrap_or_else(|| env::panic_str("Analyst not registered"));
+        require!(profile.active, "Analyst is not accepting cases");
+        require!(analyst_id != requester, "Analysts cannot be assigned their own requests");
+        self.analyst_assignments.insert(token_id, &AnalystAssignment {
+            analyst_id: analyst_id.clone(),
+            requester: requester.clone(),
+            fee: profile.price,
+            status: AssignmentStatus::Assigned,
+            assigned_at: U64(env::block_timestamp()),
+            submitted_at: None,
+        });
+    }
+
+    pub(crate) fn analyst_fee(&self, analyst_id: &Option<AccountId>) -> NearToken {
+        analyst_id.as_ref()
+            .and_then(|analyst_id| self.analysts.get(analyst_id))
+            .map_or(NearToken::from_yoctonear(0), |profile| profile.price)
+    }
+
+    /// Whether `account_id` is the analyst currently working on `token_id`
+    pub(crate) fn is_assigned_analyst(&self, token_id: &TokenId, account_id: &AccountId) -> bool {
+        self.analyst_assignments.get(token_id).is_some_and(|assignment| {
+            &assignment.analyst_id == account_id && assignment.status == AssignmentStatus::Assigned
+        })
+    }
+
+    /// Cases with an active assignment are kept out of the pipeline queue
+    pub(crate) fn has_active_assignment(&self, token_id: &TokenId) -> bool {
+        self.analyst_assignments.get(token_id).is_some_and(|assignment| {
+            assignment.status == AssignmentStatus::Assigned || assignment.status == AssignmentStatus::Submitted
+        })
+    }
+
+    pub(crate) fn internal_mark_analyst_submitted(&mut self, token_id: &TokenId) {
+        if let Some(mut assignment) = self.analyst_assignments.get(token_id) {
+            assignment.status = AssignmentStatus::Submitted;
+            assignment.submitted_at = Some(U64(env::block_timestamp()));
+            self.analyst_assignments.insert(token_id, &assignment);
+            InvestigationEvent::AnalystResultSubmitted {
+                token_id: token_id.clone(),
+                analyst_id: assignment.analyst_id.to_string(),
+                timestamp: U64(env::block_timestamp()),
+            }.log();
+        }
+    }
+
+    /// Returns an undelivered analyst fee to the requester
+    pub(crate) fn internal_refund_analyst_escrow(&mut self, token_id: &TokenId) -> NearToken {
+        match self.analyst_assignments.get(token_id) {
+            Some(mut assignment) if self.has_active_assignment(token_id) => {
+                Promise::new(assignment.requester.clone()).transfer(assignment.fee);
+                assignment.status = AssignmentStatus::Refunded;
+                self.analyst_assignments.insert(token_id, &assignment);
+                assignment.fee
+            }
+            _ => NearToken::from_yoctonear(0),
+        }
+    }
+
+    fn internal_accept_analyst_result(&mut self, token_id: TokenId, mut assignment: AnalystAssignment) -> AnalystAssignment {
+        Promise::new(assignment.analyst_id.clone()).transfer(assignment.fee);
+        assignment.status = AssignmentStatus::Accepted;
+        self.analyst_assignments.insert(&token_id, &assignment);
+        if let Some(mut profile) = self.analysts.get(&assignment.analyst_id) {
+            profile.completed_cases += 1;
+            self.analysts.insert(&assignment.analyst_id, &profile);
+        }
+        self.internal_set_status(&token_id, InvestigationStatus::Completed);
+        self.internal_record_resolution(&token_id, vec![assignment.analyst_id.clone()]);
+
+        InvestigationEvent::AnalystResultAccepted {
+            token_id,
+            analyst_id: assignment.analyst_id.to_string(),
+            fee: assignment.fee,
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+
+        assignment
+    }
+
+    fn internal_submitted_assignment(&self, token_id: &TokenId) -> AnalystAssignment {
+        let assignment = self.analyst_assignments.get(token_id)
+            .unwrap_or_else(|| env::panic_str("Case has no analyst assignment"));
+        require!(
+            assignment.status == AssignmentStatus::Submitted,
+            "No analyst result is awaiting review"
+        );
+        assignment
+    }
+}
+
+fn assert_valid_profile(profile: &AnalystProfile) {
+    require!(
+        !profile.display_name.is_empty() && profile.display_name.len() <= MAX_ANALYST_NAME_LENGTH,
+        "Invalid analyst display name"
+    );
+    require!(profile.bio.len() <= MAX_ANALYST_BIO_LENGTH, "Analyst bio too long");
+}
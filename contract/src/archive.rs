@@ -0,0 +1,116 @@
+use std::prelude::v1::*;
+use crate::*;
+use near_contract_standards::non_fungible_token::events::NftBurn;
+
+#[near_bindgen]
+impl Contract {
+    /// Burns an expired case file and drops its per-case data. Any fee still held for
+    /// an unfinished run is refunded, and the storage of the case file itself goes back
+    /// to the recorded payer, who paid for it along with the case. Evidence, notes and
+    /// ratings were paid for by their authors, so that storage is released unrefunded.
+    pub fn archive_investigation(&mut self, token_id: TokenId) -> NearToken {
+        let caller = env::predecessor_account_id();
+        let owner_id = self.tokens.owner_by_id.get(&token_id)
+            .unwrap_or_else(|| env::panic_str("Token not found"));
+        let investigation = self.get_investigation_metadata(token_id.clone())
+            .unwrap_or_else(|| env::panic_str("Investigation not found"));
+        let payer = self.case_payers.get(&token_id).unwrap_or_else(|| investigation.requester.clone());
+        require!(
+            caller == owner_id || caller == payer,
+            "Only the token owner or payer can archive a case"
+        );
+
+        let expires_at = self.get_token_metadata(token_id.clone())
+            .and_then(|metadata| metadata.expires_at)
+            .and_then(|expires_at| expires_at.parse::<u64>().ok())
+            .unwrap_or_else(|| env::panic_str("Case has no retention period"));
+        require!(env::block_timestamp() >= expires_at, "Case has not expired yet");
+        require!(
+            self.get_open_dispute(token_id.clone()).is_none(),
+            "Cannot archive a case with an open dispute"
+        );
+
+        // A completed case has earned its fee; anything else still owes the payer
+        if self.investigation_status.get(&token_id) == Some(InvestigationStatus::Completed) {
+            self.internal_settle_case(&token_id);
+            self.internal_refund_analyst_escrow(&token_id);
+        } else {
+            self.internal_refund_case(&token_id);
+        }
+
+        let initial_storage_usage = env::storage_usage();
+        self.internal_burn(&token_id, &owner_id);
+        if self.investigated_accounts.get(&investigation.target_account).as_ref() == Some(&token_id) {
+            self.investigated_accounts.remove(&investigation.target_account);
+        }
+        self.internal_unindex_by_parent(&token_id, &investigation.target_account);
+        for merged in self.merged_cases.remove(&token_id).unwrap_or_default() {
+            if let Some(merged_case) = self.get_investigation_metadata(merged) {
+                if self.investigated_accounts.get(&merged_case.target_account).as_ref() == Some(&token_id) {
+                    self.investigated_accounts.remove(&merged_case.target_account);
+                }
+            }
+        }
+        self.investigation_data.remove(&token_id);
+        self.investigation_status.remove(&token_id);
+        self.case_tiers.remove(&token_id);
+        self.case_payers.remove(&token_id);
+        self.private_cases.remove(&token_id);
+        self.retry_counts.remove(&token_id);
+        self.internal_remove_job(&token_id);
+        let freed_bytes = initial_storage_usage.saturating_sub(env::storage_usage());
+
+        self.evidence.remove(&token_id);
+        self.evidence_manifests.remove(&token_id);
+        self.attestations.remove(&token_id);
+        self.oracle_submissions.remove(&token_id);
+        self.disputes.remove(&token_id);
+        self.redactions.remove(&token_id);
+        self.case_resolutions.remove(&token_id);
+        self.analyst_assignments.remove(&token_id);
+        self.case_ratings.remove(&token_id);
+        self.internal_remove_case_notes(&token_id);
+        self.collaborators.remove(&token_id);
+        self.case_links.remove(&token_id);
+
+        let refund = env::storage_byte_cost().saturating_mul(freed_bytes as u128);
+        if !refund.is_zero() {
+            Promise::new(payer).transfer(refund);
+        }
+
+        NftBurn {
+            owner_id: &owner_id,
+            token_ids: &[&token_id],
+            authorized_id: if caller != owner_id { Some(&caller) } else { None },
+            memo: Some("archived"),
+        }.emit();
+
+        refund
+    }
+}
+
+impl Contract {
+    /// Removes a token from the NEP-171 core, enumeration and approval storage
+    pub(crate) fn internal_burn(&mut self, token_id: &TokenId, owner_id: &AccountId) {
+        self.tokens.owner_by_id.remove(token_id);
+        if let Some(token_metadata_by_id) = self.tokens.token_metadata_by_id.as_mut() {
+            token_metadata_by_id.remove(token_id);
+        }
+        if let Some(tokens_per_owner) = self.tokens.tokens_per_owner.as_mut() {
+            if let Some(mut owner_tokens) = tokens_per_owner.get(owner_id) {
+                owner_tokens.remove(token_id);
+                if owner_tokens.is_empty() {
+                    tokens_per_owner.remove(owner_id);
+                } else {
+                    tokens_per_owner.insert(owner_id, &owner_tokens);
+                }
+            }
+        }
+        if let Some(approvals_by_id) = self.tokens.approvals_by_id.as_mut() {
+            approvals_by_id.remove(token_id);
+        }
+        if let Some(next_approval_id_by_id) = self.tokens.next_approval_id_by_id.as_mut() {
+            next_approval_id_by_id.remove(token_id);
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::prelude::v1::*;
+use crate::*;
+use near_sdk::base64::{engine::general_purpose::STANDARD, Engine};
+use near_sdk::json_types::Base64VecU8;
+use crate::redaction::REDACTED_PLACEHOLDER;
+
+const MAX_TARGET_CHARS: usize = 30;
+/// Longest `InvestigationStatus` and `RiskLevel` names, used to pad the card text
+const STATUS_CHARS: usize = 11;
+const RISK_CHARS: usize = 10;
+
+/// Renders the case file card shown by wallets: case number, target, status badge,
+/// risk level and last update time. With `hide_target` the target and risk are
+/// replaced by a placeholder.
+///
+/// Text fields are padded to a fixed width (trailing spaces are not drawn), so a
+/// case's card keeps the same size whenever it is re-rendered.
+pub fn render_case_svg(investigation: &InvestigationMetadata, hide_target: bool) -> String {
+    let target = investigation.target_account.as_str();
+    let target = if hide_target {
+        REDACTED_PLACEHOLDER.to_string()
+    } else if target.chars().count() > MAX_TARGET_CHARS {
+        format!("{}...", target.chars().take(MAX_TARGET_CHARS - 3).collect::<String>())
+    } else {
+        target.to_string()
+    };
+    let status = format!("{:?}", investigation.status);
+    let risk = if hide_target {
+        REDACTED_PLACEHOLDER.to_string()
+    } else {
+        format!("{:?}", investigation.risk_level)
+    };
+    let risk_color = if hide_target { "#9ca3af" } else { risk_color(&investigation.risk_level) };
+
+    format!(
+        "<svg xmlns='http://www.w3.org/2000/svg' width='400' height='400' viewBox='0 0 400 400'>\
+<rect width='400' height='400' rx='16' fill='#111827'/>\
+<text x='24' y='56' font-family='monospace' font-size='22' fill='#f9fafb'>CASE FILE #{case_number}</text>\
+<text x='24' y='96' font-family='monospace' font-size='16' fill='#d1d5db'>{target:<target_width$}</text>\
+<rect x='24' y='136' width='{badge_width}' height='36' rx='18' fill='{status_color}'/>\
+<text x='44' y='160' font-family='monospace' font-size='16' fill='#111827'>{status:<status_width$}</text>\
+<text x='24' y='228' font-family='monospace' font-size='14' fill='#9ca3af'>RISK</text>\
+<text x='24' y='256' font-family='monospace' font-size='20' fill='{risk_color}'>{risk:<risk_width$}</text>\
+<text x='24' y='368' font-family='monospace' font-size='12' fill='#6b7280'>Updated {updated}</text>\
+</svg>",
+        case_number = investigation.case_number,
+        target = escape_xml(&target),
+        target_width = MAX_TARGET_CHARS,
+        badge_width = 40 + status.len() * 10,
+        status_color = status_color(&investigation.status),
+        status = status,
+        status_width = STATUS_CHARS,
+        risk_color = risk_color,
+        risk = risk,
+        risk_width = RISK_CHARS,
+        updated = format_timestamp(investigation.last_updated.0),
+    )
+}
+
+/// The SVG as a `data:` URI for `media`, with its sha256 for `media_hash`
+pub fn case_artwork(investigation: &InvestigationMetadata, hide_target: bool) -> (String, Base64VecU8) {
+    let svg = render_case_svg(investigation, hide_target);
+    let media_hash = Base64VecU8::from(env::sha256(svg.as_bytes()));
+    (format!("data:image/svg+xml;base64,{}", STANDARD.encode(svg)), media_hash)
+}
+
+fn status_color(status: &InvestigationStatus) -> &'static str {
+    match status {
+        InvestigationStatus::Pending => "#9ca3af",
+        InvestigationStatus::Processing => "#60a5fa",
+        InvestigationStatus::Completed => "#34d399",
+        InvestigationStatus::Failed => "#f87171",
+        InvestigationStatus::Disputed => "#fbbf24",
+        InvestigationStatus::Merged => "#a78bfa",
+        InvestigationStatus::UnderReview => "#38bdf8",
+        InvestigationStatus::NoConsensus => "#fb923c",
+    }
+}
+
+fn risk_color(risk_level: &RiskLevel) -> &'static str {
+    match risk_level {
+        RiskLevel::Unknown => "#9ca3af",
+        RiskLevel::Low => "#34d399",
+        RiskLevel::Medium => "#fbbf24",
+        RiskLevel::High => "#fb923c",
+        RiskLevel::Critical => "#f87171",
+    }
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\'', "&apos;")
+}
+
+/// Formats a nanosecond block timestamp as `YYYY-MM-DD HH:MM UTC`
+fn format_timestamp(timestamp_ns: u64) -> String {
+    let seconds = timestamp_ns / 1_000_000_000;
+    let (days, seconds_of_day) = (seconds / 86_400, seconds % 86_400);
+
+    // Civil-from-days conversion (proleptic Gregorian calendar)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        seconds_of_day / 3_600,
+        seconds_of_day % 3_600 / 60
+    )
+}
+
+impl Contract {
+    /// Redacted and private cases and opted-out targets get a card without the target or risk
+    pub(crate) fn hides_artwork_target(&self, investigation: &InvestigationMetadata) -> bool {
+        investigation.redacted
+            || investigation.visibility == Visibility::Private
+            || self.opted_out_accounts.contains(&investigation.target_account)
+    }
+
+    /// Re-renders the token artwork so wallets show the current case state.
+    /// The mint paid for the card; should a re-render still grow storage, the
+    /// extra bytes are taken out of the case payment instead of the contract balance.
+    pub(crate) fn internal_refresh_artwork(&mut self, token_id: &TokenId, investigation: &InvestigationMetadata) {
+        let initial_storage_usage = env::storage_usage();
+        let (media, media_hash) = case_artwork(investigation, self.hides_artwork_target(investigation));
+        self.update_token_metadata(token_id, |metadata| {
+            metadata.media = Some(media);
+            metadata.media_hash = Some(media_hash);
+            metadata.updated_at = Some(env::block_timestamp().to_string());
+        });
+
+        let storage_cost = env::storage_byte_cost()
+            .saturating_mul(env::storage_usage().saturating_sub(initial_storage_usage) as u128);
+        if !storage_cost.is_zero() {
+            if let Some(payment) = self.case_payments.get(token_id) {
+                self.case_payments.insert(token_id, &payment.saturating_sub(storage_cost));
+            }
+        }
+    }
+}
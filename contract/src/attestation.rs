@@ -0,0 +1,154 @@
+use std::prelude::v1::*;
+use crate::*;
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{CurveType, PublicKey};
+
+/// Accepted attestations kept per case; older ones are dropped first
+pub const MAX_ATTESTATIONS_PER_CASE: usize = 20;
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AttestationKey {
+    pub public_key: PublicKey,
+    pub added_at: U64,
+    pub revoked_at: Option<U64>,
+}
+
+/// Signature produced by the analysis pipeline over `attestation_message(token_id, nonce, metadata)`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SignedAttestation {
+    pub public_key: PublicKey,
+    pub signature: Base64VecU8,
+    /// Must be greater than the case's last accepted nonce, see `get_attestation_nonce`
+    pub nonce: U64,
+}
+
+/// Audit record of an accepted attestation
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Attestation {
+    pub public_key: PublicKey,
+    pub signature: Base64VecU8,
+    pub nonce: U64,
+    pub status: InvestigationStatus,
+    pub submitted_by: AccountId,
+    pub timestamp: U64,
+}
+
+/// Canonical bytes signed by the pipeline: borsh of `(token_id, nonce, metadata)`
+pub fn attestation_message(token_id: &TokenId, nonce: u64, metadata: &InvestigationMetadata) -> Vec<u8> {
+    borsh::to_vec(&(token_id, nonce, metadata)).unwrap_or_else(|_| env::panic_str("Failed to serialize attestation"))
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Registers a new signing key. Keys are never reused, so a revoked key stays revoked.
+    pub fn add_attestation_key(&mut self, public_key: PublicKey) {
+        self.assert_owner();
+        require!(public_key.curve_type() == CurveType::ED25519, "Only ed25519 keys are supported");
+        require!(
+            self.attestation_keys.get(&public_key).is_none(),
+            "Attestation key already registered"
+        );
+        let key = AttestationKey {
+            public_key: public_key.clone(),
+            added_at: U64(env::block_timestamp()),
+            revoked_at: None,
+        };
+        self.attestation_keys.insert(&public_key, &key);
+        InvestigationEvent::AttestationKeyAdded {
+            public_key: String::from(&public_key),
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+    }
+
+    pub fn revoke_attestation_key(&mut self, public_key: PublicKey) {
+        self.assert_owner();
+        let mut key = self.attestation_keys.get(&public_key)
+            .unwrap_or_else(|| env::panic_str("Attestation key not found"));
+        require!(key.revoked_at.is_none(), "Attestation key already revoked");
+        key.revoked_at = Some(U64(env::block_timestamp()));
+        self.attestation_keys.insert(&public_key, &key);
+        InvestigationEvent::AttestationKeyRevoked {
+            public_key: String::from(&public_key),
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+    }
+
+    pub fn rotate_attestation_key(&mut self, old_public_key: PublicKey, new_public_key: PublicKey) {
+        self.revoke_attestation_key(old_public_key);
+        self.add_attestation_key(new_public_key);
+    }
+
+    /// When enabled, unsigned updates are rejected even from the owner
+    pub fn set_require_attestation(&mut self, required: bool) {
+        self.assert_owner();
+        self.require_attestation = required;
+    }
+
+    pub fn get_attestation_keys(&self) -> Vec<AttestationKey> {
+        self.attestation_keys.values().collect()
+    }
+
+    pub fn get_attestations(&self, token_id: TokenId) -> Vec<Attestation> {
+        self.attestations.get(&token_id).unwrap_or_default()
+    }
+
+    /// Last nonce accepted for the case; the next attestation must use a larger one
+    pub fn get_attestation_nonce(&self, token_id: TokenId) -> U64 {
+        U64(self.internal_attestation_nonce(&token_id))
+    }
+}
+
+impl Contract {
+    /// Checks `attestation` against the key registry and records it with the case
+    pub(crate) fn verify_attestation(
+        &mut self,
+        token_id: &TokenId,
+        metadata: &InvestigationMetadata,
+        attestation: &SignedAttestation,
+    ) -> Result<(), String> {
+        let key = self.attestation_keys.get(&attestation.public_key)
+            .ok_or_else(|| "Unknown attestation key".to_string())?;
+        if key.revoked_at.is_some() {
+            return Err("Attestation key has been revoked".to_string());
+        }
+
+        let signature: [u8; 64] = attestation.signature.0.as_slice().try_into()
+            .map_err(|_| "Signature must be 64 bytes".to_string())?;
+        let public_key: [u8; 32] = attestation.public_key.as_bytes()[1..].try_into()
+            .map_err(|_| "Invalid ed25519 public key".to_string())?;
+        let message = attestation_message(token_id, attestation.nonce.0, metadata);
+        if !env::ed25519_verify(&signature, &message, &public_key) {
+            return Err("Invalid attestation signature".to_string());
+        }
+
+        // Each signed result is bound to a per-case nonce, so it can only be applied once
+        if attestation.nonce.0 <= self.internal_attestation_nonce(token_id) {
+            return Err("Attestation nonce has already been used".to_string());
+        }
+
+        let mut attestations = self.attestations.get(token_id).unwrap_or_default();
+        if attestations.len() >= MAX_ATTESTATIONS_PER_CASE {
+            attestations.remove(0);
+        }
+        attestations.push(Attestation {
+            public_key: attestation.public_key.clone(),
+            signature: attestation.signature.clone(),
+            nonce: attestation.nonce,
+            status: metadata.status.clone(),
+            submitted_by: env::predecessor_account_id(),
+            timestamp: U64(env::block_timestamp()),
+        });
+        self.attestations.insert(token_id, &attestations);
+        Ok(())
+    }
+
+    pub(crate) fn internal_attestation_nonce(&self, token_id: &TokenId) -> u64 {
+        self.attestations.get(token_id)
+            .and_then(|attestations| attestations.last().map(|attestation| attestation.nonce.0))
+            .unwrap_or(0)
+    }
+}
@@ -0,0 +1,115 @@
+use std::prelude::v1::*;
+use std::collections::HashSet;
+use crate::*;
+
+/// Hard cap on targets per `start_investigations` call, to stay within the gas limit
+pub const MAX_BATCH_SIZE: usize = 50;
+
+#[near_bindgen]
+impl Contract {
+    /// Opens cases for several targets in one transaction. Targets that already have a
+    /// fresh case, are opted out, or repeat within the batch are skipped and reported as
+    /// such; failed or stale cases are re-queued instead. The attached deposit must cover
+    /// the tier price for every case created or re-queued plus the storage they use.
+    #[payable]
+    pub fn start_investigations(
+        &mut self,
+        targets: Vec<AccountId>,
+        options: Option<InvestigationOptions>,
+    ) -> Vec<InvestigationResponse> {
+        require!(!targets.is_empty(), "No targets provided");
+        require!(
+            targets.len() <= MAX_BATCH_SIZE,
+            format!("Cannot start more than {} investigations per call", MAX_BATCH_SIZE)
+        );
+        let options = options.unwrap_or_default();
+        options.assert_valid();
+        require!(options.analyst.is_none(), "Analysts must be selected per investigation");
+
+        let initial_storage_usage = env::storage_usage();
+        let requester = env::predecessor_account_id();
+        let privileged = self.is_privileged(&requester);
+        let price = self.tier_config(&options.tier).price;
+        let mut seen: HashSet<AccountId> = HashSet::new();
+        let mut responses = Vec::with_capacity(targets.len());
+        let mut created: Vec<TokenId> = Vec::new();
+        let mut fees = NearToken::from_yoctonear(0);
+
+        for target in targets {
+            if !seen.insert(target.clone()) {
+                responses.push(InvestigationResponse {
+                    request_id: String::new(),
+                    status: InvestigationStatus::Failed,
+                    message: Some(format!("Duplicate target {}", target)),
+                });
+                continue;
+            }
+            if self.opted_out_accounts.contains(&target) && !privileged {
+                responses.push(InvestigationResponse {
+                    request_id: String::new(),
+                    status: InvestigationStatus::Failed,
+                    message: Some(format!("{} has opted out of investigations", target)),
+                });
+                continue;
+            }
+            if let Some(token_id) = self.investigated_accounts.get(&target) {
+                let status = self.investigation_status.get(&token_id).unwrap_or(InvestigationStatus::Failed);
+                if !status.is_retryable() && !self.is_stale(&token_id) {
+                    responses.push(InvestigationResponse {
+                        status,
+                        request_id: token_id,
+                        message: Some("Investigation already exists".to_string()),
+                    });
+                    continue;
+                }
+                let price = self.tier_config(&self.case_tiers.get(&token_id).unwrap_or_default()).price;
+                self.internal_reopen_case(&token_id, &requester, price);
+                fees = fees.saturating_add(price);
+                created.push(token_id.clone());
+                responses.push(InvestigationResponse {
+                    request_id: token_id,
+                    status: InvestigationStatus::Pending,
+                    message: Some("Investigation re-queued".to_string()),
+                });
+                continue;
+            }
+
+            let (token_id, _) = self.internal_create_case(&target, &options, &requester);
+            self.internal_record_payment(&token_id, &requester, price);
+            fees = fees.saturating_add(price);
+            created.push(token_id.clone());
+            responses.push(InvestigationResponse {
+                request_id: token_id,
+                status: InvestigationStatus::Pending,
+                message: None,
+            });
+        }
+
+        // Storage for every mint and re-queue is paid from the batch deposit
+        let storage_cost = env::storage_byte_cost()
+            .saturating_mul(env::storage_usage().saturating_sub(initial_storage_usage) as u128);
+        let required_deposit = fees.saturating_add(storage_cost);
+        let deposit = env::attached_deposit();
+        require!(
+            deposit >= required_deposit,
+            format!(
+                "Must attach at least {} for {} investigations",
+                required_deposit.exact_amount_display(),
+                created.len()
+            )
+        );
+        if deposit > required_deposit {
+            Promise::new(requester.clone()).transfer(deposit.saturating_sub(required_deposit));
+        }
+
+        if !created.is_empty() {
+            InvestigationEvent::BatchStarted {
+                requester: requester.to_string(),
+                token_ids: created,
+                timestamp: U64(env::block_timestamp()),
+            }.log();
+        }
+
+        responses
+    }
+}
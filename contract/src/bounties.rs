@@ -0,0 +1,261 @@
+use std::prelude::v1::*;
+use crate::*;
+use near_sdk::serde::{Deserialize, Serialize};
+
+pub const MIN_BOUNTY_REWARD: NearToken = INVESTIGATION_FEE;
+pub const MAX_BOUNTY_CONTRIBUTORS: usize = 50;
+/// 3 days for the target to dispute a result before a bounty on it can be claimed
+pub const BOUNTY_DISPUTE_WINDOW_NS: u64 = 3 * 24 * 60 * 60 * 1_000_000_000;
+const MAX_BOUNTY_LIMIT: u64 = 100;
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum BountyStatus {
+    Open,
+    Paid,
+    Refunded,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BountyContribution {
+    pub account_id: AccountId,
+    pub amount: NearToken,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Bounty {
+    pub id: u64,
+    pub target_account: AccountId,
+    pub creator: AccountId,
+    pub reward: NearToken,
+    pub contributions: Vec<BountyContribution>,
+    pub deadline: U64,
+    pub status: BountyStatus,
+    pub paid_to: Vec<AccountId>,
+    pub created_at: U64,
+}
+
+/// Who produced the accepted result of a case, used to route bounty payouts
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CaseResolution {
+    pub resolvers: Vec<AccountId>,
+    pub completed_at: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Opens a bounty on `target_account` funded with `reward`; any excess deposit is refunded
+    #[payable]
+    pub fn create_bounty(&mut self, target_account: AccountId, reward: NearToken, deadline: U64) -> Bounty {
+        let creator = env::predecessor_account_id();
+        require!(reward >= MIN_BOUNTY_REWARD, "Bounty reward is below the minimum");
+        require!(deadline.0 > env::block_timestamp(), "Deadline must be in the future");
+        require!(
+            !self.opted_out_accounts.contains(&target_account) || self.is_privileged(&creator),
+            "Target account has opted out of investigations"
+        );
+        let deposit = env::attached_deposit();
+        require!(
+            deposit >= reward,
+            format!("Must attach {} to fund the bounty", reward.exact_amount_display())
+        );
+        if deposit > reward {
+            Promise::new(creator.clone()).transfer(deposit.saturating_sub(reward));
+        }
+
+        let bounty = Bounty {
+            id: self.next_bounty_id,
+            target_account,
+            creator: creator.clone(),
+            reward,
+            contributions: vec![BountyContribution { account_id: creator.clone(), amount: reward }],
+            deadline,
+            status: BountyStatus::Open,
+            paid_to: Vec::new(),
+            created_at: U64(env::block_timestamp()),
+        };
+        self.next_bounty_id += 1;
+        self.bounties.insert(&bounty.id, &bounty);
+        self.open_bounties.insert(&bounty.id);
+        let mut target_bounties = self.bounties_by_target.get(&bounty.target_account).unwrap_or_default();
+        target_bounties.push(bounty.id);
+        self.bounties_by_target.insert(&bounty.target_account, &target_bounties);
+
+        InvestigationEvent::BountyCreated {
+            bounty_id: U64(bounty.id),
+            target_account: bounty.target_account.to_string(),
+            creator: creator.to_string(),
+            reward,
+            deadline,
+        }.log();
+
+        bounty
+    }
+
+    #[payable]
+    pub fn contribute_to_bounty(&mut self, bounty_id: U64) -> NearToken {
+        let contributor = env::predecessor_account_id();
+        let amount = env::attached_deposit();
+        require!(!amount.is_zero(), "Contribution must be greater than zero");
+        let mut bounty = self.internal_open_bounty(bounty_id.0);
+        require!(env::block_timestamp() < bounty.deadline.0, "Bounty has expired");
+
+        match bounty.contributions.iter_mut().find(|contribution| contribution.account_id == contributor) {
+            Some(contribution) => contribution.amount = contribution.amount.saturating_add(amount),
+            None => {
+                require!(
+                    bounty.contributions.len() < MAX_BOUNTY_CONTRIBUTORS,
+                    "Bounty has reached the maximum number of contributors"
+                );
+                bounty.contributions.push(BountyContribution { account_id: contributor.clone(), amount });
+            }
+        }
+        bounty.reward = bounty.reward.saturating_add(amount);
+        self.bounties.insert(&bounty.id, &bounty);
+
+        InvestigationEvent::BountyContribution {
+            bounty_id,
+            contributor: contributor.to_string(),
+            amount,
+            reward: bounty.reward,
+        }.log();
+
+        bounty.reward
+    }
+
+    /// Pays the reward to whoever produced the target's `Completed` result after the bounty
+    /// was opened, once the dispute window on that result has closed. Consensus results split
+    /// the reward between the agreeing oracles; the bounty creator and the owner never share.
+    pub fn claim_bounty(&mut self, bounty_id: U64) -> Vec<AccountId> {
+        let mut bounty = self.internal_open_bounty(bounty_id.0);
+        let (token_id, resolution) = self.internal_bounty_resolution(&bounty)
+            .unwrap_or_else(|| env::panic_str("No accepted result since the bounty was opened"));
+        require!(
+            env::block_timestamp() >= resolution.completed_at.0.saturating_add(BOUNTY_DISPUTE_WINDOW_NS),
+            "The dispute window on the result has not passed yet"
+        );
+        require!(self.get_open_dispute(token_id.clone()).is_none(), "The result is under dispute");
+
+        let count = resolution.resolvers.len() as u128;
+        let share = bounty.reward.as_yoctonear() / count;
+        let remainder = bounty.reward.as_yoctonear() % count;
+        for (index, resolver) in resolution.resolvers.iter().enumerate() {
+            let amount = if index == 0 { share + remainder } else { share };
+            Promise::new(resolver.clone()).transfer(NearToken::from_yoctonear(amount));
+        }
+
+        bounty.status = BountyStatus::Paid;
+        bounty.paid_to = resolution.resolvers.clone();
+        self.internal_close_bounty(&bounty);
+
+        InvestigationEvent::BountyPaid {
+            bounty_id,
+            token_id,
+            recipients: resolution.resolvers.iter().map(|resolver| resolver.to_string()).collect(),
+            reward: bounty.reward,
+        }.log();
+
+        resolution.resolvers
+    }
+
+    /// Returns every contribution once the deadline has passed without a claimable result
+    pub fn refund_bounty(&mut self, bounty_id: U64) -> NearToken {
+        let mut bounty = self.internal_open_bounty(bounty_id.0);
+        require!(env::block_timestamp() >= bounty.deadline.0, "Bounty has not expired yet");
+        require!(
+            self.internal_bounty_resolution(&bounty).is_none(),
+            "Bounty has a valid resolution and must be claimed"
+        );
+        self.internal_refund_bounty(&mut bounty);
+        bounty.reward
+    }
+
+    pub fn get_bounty(&self, bounty_id: U64) -> Option<Bounty> {
+        self.bounties.get(&bounty_id.0)
+    }
+
+    pub fn get_open_bounties(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<Bounty> {
+        let limit = limit.unwrap_or(MAX_BOUNTY_LIMIT).min(MAX_BOUNTY_LIMIT) as usize;
+        self.open_bounties
+            .iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit)
+            .filter_map(|id| self.bounties.get(&id))
+            .collect()
+    }
+
+    pub fn get_case_resolution(&self, token_id: TokenId) -> Option<CaseResolution> {
+        self.case_resolutions.get(&token_id)
+    }
+}
+
+impl Contract {
+    fn internal_open_bounty(&self, bounty_id: u64) -> Bounty {
+        let bounty = self.bounties.get(&bounty_id)
+            .unwrap_or_else(|| env::panic_str("Bounty not found"));
+        require!(bounty.status == BountyStatus::Open, "Bounty is closed");
+        bounty
+    }
+
+    /// The completed case for the bounty target, if it was resolved after the bounty opened
+    /// by someone other than the bounty creator or the owner. Only those resolvers are kept.
+    fn internal_bounty_resolution(&self, bounty: &Bounty) -> Option<(TokenId, CaseResolution)> {
+        let token_id = self.investigated_accounts.get(&bounty.target_account)?;
+        if self.investigation_status.get(&token_id) != Some(InvestigationStatus::Completed) {
+            return None;
+        }
+        let mut resolution = self.case_resolutions.get(&token_id)
+            .filter(|resolution| resolution.completed_at.0 >= bounty.created_at.0)?;
+        resolution.resolvers.retain(|resolver| resolver != &bounty.creator && resolver != &self.owner_id);
+        if resolution.resolvers.is_empty() {
+            return None;
+        }
+        Some((token_id, resolution))
+    }
+
+    fn internal_refund_bounty(&mut self, bounty: &mut Bounty) {
+        for contribution in bounty.contributions.iter() {
+            Promise::new(contribution.account_id.clone()).transfer(contribution.amount);
+        }
+        bounty.status = BountyStatus::Refunded;
+        self.internal_close_bounty(bounty);
+
+        InvestigationEvent::BountyRefunded {
+            bounty_id: U64(bounty.id),
+            contributors: bounty.contributions.len() as u32,
+            reward: bounty.reward,
+        }.log();
+    }
+
+    fn internal_close_bounty(&mut self, bounty: &Bounty) {
+        self.bounties.insert(&bounty.id, bounty);
+        self.open_bounties.remove(&bounty.id);
+        let mut target_bounties = self.bounties_by_target.get(&bounty.target_account).unwrap_or_default();
+        target_bounties.retain(|id| *id != bounty.id);
+        if target_bounties.is_empty() {
+            self.bounties_by_target.remove(&bounty.target_account);
+        } else {
+            self.bounties_by_target.insert(&bounty.target_account, &target_bounties);
+        }
+    }
+
+    /// Refunds every open bounty on `target_account` regardless of its deadline
+    pub(crate) fn internal_refund_target_bounties(&mut self, target_account: &AccountId) {
+        for bounty_id in self.bounties_by_target.get(target_account).unwrap_or_default() {
+            if let Some(mut bounty) = self.bounties.get(&bounty_id) {
+                self.internal_refund_bounty(&mut bounty);
+            }
+        }
+    }
+
+    pub(crate) fn internal_record_resolution(&mut self, token_id: &TokenId, resolvers: Vec<AccountId>) {
+        self.internal_credit_completion(token_id, &resolvers);
+        self.case_resolutions.insert(token_id, &CaseResolution {
+            resolvers,
+            completed_at: U64(env::block_timestamp()),
+        });
+    }
+}
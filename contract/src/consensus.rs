@@ -0,0 +1,196 @@
+use std::prelude::v1::*;
+use crate::*;
+use crate::privacy::{assert_private_update, strip_sensitive_fields};
+use near_sdk::serde::{Deserialize, Serialize};
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ConsensusConfig {
+    pub enabled: bool,
+    /// Number of agreeing oracle submissions needed to finalize a case
+    pub quorum: u32,
+    /// Allowed relative difference in `transaction_count`, in basis points
+    pub transaction_count_tolerance_bps: u32,
+}
+
+impl Default for ConsensusConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            quorum: 1,
+            transaction_count_tolerance_bps: 0,
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OracleSubmission {
+    pub oracle_id: AccountId,
+    pub metadata: InvestigationMetadata,
+    pub submitted_at: U64,
+}
+
+impl ConsensusConfig {
+    /// Two submissions agree when `is_bot` and risk level match and the
+    /// transaction counts are within the configured tolerance
+    pub fn agrees(&self, a: &InvestigationMetadata, b: &InvestigationMetadata) -> bool {
+        if a.is_bot != b.is_bot || a.risk_level != b.risk_level {
+            return false;
+        }
+        let diff = a.transaction_count.abs_diff(b.transaction_count) as u128;
+        let base = a.transaction_count.max(b.transaction_count) as u128;
+        diff * 10_000 <= base * self.transaction_count_tolerance_bps as u128
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Deregisters an oracle and bars it from re-registering until reinstated;
+    /// its stake remains withdrawable through `unstake`
+    pub fn remove_oracle(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.removed_oracles.insert(&account_id);
+        if self.oracles.remove(&account_id) {
+            InvestigationEvent::OracleRemoved {
+                oracle_id: account_id.to_string(),
+                timestamp: U64(env::block_timestamp()),
+            }.log();
+        }
+    }
+
+    /// Lets a removed oracle register again; it still has to meet the minimum stake
+    pub fn reinstate_oracle(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        require!(self.removed_oracles.remove(&account_id), "Oracle was not removed");
+        if self.oracle_stakes.get(&account_id)
+            .is_some_and(|stake| stake.staked >= self.staking_config.min_oracle_stake)
+        {
+            self.oracles.insert(&account_id);
+        }
+        InvestigationEvent::OracleReinstated {
+            oracle_id: account_id.to_string(),
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+    }
+
+    pub fn get_oracles(&self) -> Vec<AccountId> {
+        self.oracles.to_vec()
+    }
+
+    pub fn set_consensus_config(&mut self, config: ConsensusConfig) {
+        self.assert_owner();
+        require!(config.quorum >= 1, "Quorum must be at least 1");
+        require!(
+            !config.enabled || config.quorum as u64 <= self.oracles.len(),
+            "Quorum cannot exceed the number of staked oracles"
+        );
+        self.consensus_config = config;
+    }
+
+    pub fn get_consensus_config(&self) -> ConsensusConfig {
+        self.consensus_config.clone()
+    }
+
+    /// Records one oracle's result. The case is finalized as `Completed` once a quorum
+    /// of submissions agree, or moved to `NoConsensus` when a quorum can no longer be reached.
+    pub fn submit_result(&mut self, token_id: TokenId, metadata_update: MetadataUpdate) -> InvestigationStatus {
+        let oracle_id = env::predecessor_account_id();
+        require!(self.is_staked_oracle(&oracle_id), "Only staked oracles can submit results");
+        require!(self.consensus_config.enabled, "Consensus mode is not enabled");
+
+        let status = self.investigation_status.get(&token_id)
+            .unwrap_or_else(|| env::panic_str("Investigation not found"));
+        require!(
+            status == InvestigationStatus::Pending || status == InvestigationStatus::Processing,
+            "Investigation is not awaiting results"
+        );
+        if let Err(e) = metadata_update.validate(self.max_extra_size) {
+            env::panic_str(&e);
+        }
+        let mut metadata: InvestigationMetadata = serde_json::from_str(&metadata_update.extra)
+            .unwrap_or_else(|e| env::panic_str(&format!("Metadata deserialization failed: {}", e)));
+        let stored = self.internal_investigation_metadata(&token_id)
+            .unwrap_or_else(|| env::panic_str("Investigation not found"));
+        metadata.keep_case_identity(&stored);
+        self.apply_redactions(&token_id, &mut metadata);
+        if self.is_private_case(&token_id) {
+            assert_private_update(&metadata);
+            strip_sensitive_fields(&mut metadata);
+        }
+
+        let mut submissions = self.oracle_submissions.get(&token_id).unwrap_or_default();
+        require!(
+            submissions.iter().all(|submission| submission.oracle_id != oracle_id),
+            "Oracle already submitted a result for this case"
+        );
+        submissions.push(OracleSubmission {
+            oracle_id: oracle_id.clone(),
+            metadata: metadata.clone(),
+            submitted_at: U64(env::block_timestamp()),
+        });
+        self.oracle_submissions.insert(&token_id, &submissions);
+
+        InvestigationEvent::ResultSubmitted {
+            token_id: token_id.clone(),
+            oracle_id: oracle_id.to_string(),
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+
+        let quorum = self.consensus_config.quorum as usize;
+        let (best_index, best_agreement) = submissions
+            .iter()
+            .enumerate()
+            .map(|(index, candidate)| {
+                let agreeing = submissions
+                    .iter()
+                    .filter(|other| self.consensus_config.agrees(&candidate.metadata, &other.metadata))
+                    .count();
+                (index, agreeing)
+            })
+            .max_by_key(|(_, agreeing)| *agreeing)
+            .unwrap_or((0, 0));
+        let outstanding = (self.oracles.len() as usize).saturating_sub(submissions.len());
+
+        if best_agreement >= quorum {
+            let mut result = submissions[best_index].metadata.clone();
+            result.status = InvestigationStatus::Completed;
+            result.last_updated = U64(env::block_timestamp());
+            self.apply_label_rolldown(&mut result);
+            self.investigation_data.insert(&token_id, &result);
+            self.investigation_status.insert(&token_id, &InvestigationStatus::Completed);
+            self.internal_sync_job(&token_id, &InvestigationStatus::Completed);
+            self.internal_notify_watchers(&token_id, &result);
+            self.internal_refresh_artwork(&token_id, &result);
+            self.internal_settle_case(&token_id);
+            let agreeing_oracles = submissions
+                .iter()
+                .filter(|other| self.consensus_config.agrees(&submissions[best_index].metadata, &other.metadata))
+                .map(|submission| submission.oracle_id.clone())
+                .collect();
+            self.internal_record_resolution(&token_id, agreeing_oracles);
+            InvestigationEvent::ConsensusReached {
+                token_id,
+                agreeing_oracles: best_agreement as u32,
+                timestamp: U64(env::block_timestamp()),
+            }.log();
+            InvestigationStatus::Completed
+        } else if best_agreement + outstanding < quorum {
+            self.internal_set_status(&token_id, InvestigationStatus::NoConsensus);
+            InvestigationEvent::ConsensusFailed {
+                token_id,
+                submissions: submissions.len() as u32,
+                timestamp: U64(env::block_timestamp()),
+            }.log();
+            InvestigationStatus::NoConsensus
+        } else {
+            self.investigation_status.insert(&token_id, &InvestigationStatus::Processing);
+            self.internal_sync_job(&token_id, &InvestigationStatus::Processing);
+            InvestigationStatus::Processing
+        }
+    }
+
+    pub fn get_oracle_submissions(&self, token_id: TokenId) -> Vec<OracleSubmission> {
+        self.oracle_submissions.get(&token_id).unwrap_or_default()
+    }
+}
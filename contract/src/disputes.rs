@@ -0,0 +1,171 @@
+use std::prelude::v1::*;
+use crate::*;
+use crate::evidence::MAX_EVIDENCE_URI_LENGTH;
+use near_sdk::serde::{Deserialize, Serialize};
+
+pub const DEFAULT_DISPUTE_BOND: NearToken = NearToken::from_near(1);
+pub const MAX_DISPUTE_REASON_LENGTH: usize = 1_000;
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum DisputeOutcome {
+    /// The dispute is valid: the bond is refunded and the case is re-queued for analysis
+    Upheld,
+    /// The published result stands: the bond is forfeited and the previous status restored
+    Rejected,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DisputeResolution {
+    pub outcome: DisputeOutcome,
+    pub note: String,
+    pub resolved_by: AccountId,
+    pub resolved_at: U64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Dispute {
+    pub opened_by: AccountId,
+    pub reason: String,
+    pub evidence_uri: Option<String>,
+    pub bond: NearToken,
+    pub previous_status: InvestigationStatus,
+    pub opened_at: U64,
+    pub resolution: Option<DisputeResolution>,
+}
+
+#[near_bindgen]
+impl Contract {
+    #[payable]
+    pub fn open_dispute(&mut self, token_id: TokenId, reason: String, evidence_uri: Option<String>) -> Dispute {
+        let caller = env::predecessor_account_id();
+        let investigation = self.get_investigation_metadata(token_id.clone())
+            .unwrap_or_else(|| env::panic_str("Investigation not found"));
+        let owner = self.tokens.owner_by_id.get(&token_id);
+        require!(
+            caller == investigation.target_account || Some(&caller) == owner.as_ref(),
+            "Only the investigated account or token owner can open a dispute"
+        );
+        require!(
+            !reason.is_empty() && reason.len() <= MAX_DISPUTE_REASON_LENGTH,
+            "Invalid dispute reason"
+        );
+        if let Some(uri) = &evidence_uri {
+            require!(uri.len() <= MAX_EVIDENCE_URI_LENGTH, "Evidence URI too long");
+        }
+        let bond = env::attached_deposit();
+        require!(
+            bond >= self.dispute_bond,
+            format!("Must attach a bond of at least {}", self.dispute_bond.exact_amount_display())
+        );
+
+        let status = self.investigation_status.get(&token_id)
+            .unwrap_or_else(|| env::panic_str("Investigation not found"));
+        require!(status == InvestigationStatus::Completed, "Only completed cases can be disputed");
+
+        let dispute = Dispute {
+            opened_by: caller.clone(),
+            reason,
+            evidence_uri,
+            bond,
+            previous_status: status,
+            opened_at: U64(env::block_timestamp()),
+            resolution: None,
+        };
+        let mut disputes = self.disputes.get(&token_id).unwrap_or_default();
+        disputes.push(dispute.clone());
+        self.disputes.insert(&token_id, &disputes);
+        self.internal_set_status(&token_id, InvestigationStatus::Disputed);
+
+        InvestigationEvent::DisputeOpened {
+            token_id,
+            opened_by: caller.to_string(),
+            bond,
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+
+        dispute
+    }
+
+    pub fn resolve_dispute(&mut self, token_id: TokenId, outcome: DisputeOutcome, note: String) -> Dispute {
+        self.assert_moderator();
+        let mut disputes = self.disputes.get(&token_id).unwrap_or_default();
+        let dispute = disputes.last_mut()
+            .filter(|dispute| dispute.resolution.is_none())
+            .unwrap_or_else(|| env::panic_str("No open dispute for this case"));
+
+        dispute.resolution = Some(DisputeResolution {
+            outcome: outcome.clone(),
+            note,
+            resolved_by: env::predecessor_account_id(),
+            resolved_at: U64(env::block_timestamp()),
+        });
+        let resolved = dispute.clone();
+        self.disputes.insert(&token_id, &disputes);
+
+        match outcome {
+            DisputeOutcome::Upheld => {
+                let resolvers = self.case_resolutions.get(&token_id).map(|resolution| resolution.resolvers).unwrap_or_default();
+                for resolver in &resolvers {
+                    self.internal_update_reputation(resolver, |reputation| reputation.disputes_lost += 1);
+                }
+                self.internal_slash_resolvers(&token_id, &resolvers);
+                self.internal_set_status(&token_id, InvestigationStatus::Pending);
+                Promise::new(resolved.opened_by.clone()).transfer(resolved.bond);
+            }
+            DisputeOutcome::Rejected => {
+                self.internal_set_status(&token_id, resolved.previous_status.clone());
+                self.forfeited_bonds = self.forfeited_bonds.saturating_add(resolved.bond);
+            }
+        }
+
+        InvestigationEvent::DisputeResolved {
+            token_id,
+            outcome: format!("{:?}", outcome),
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+
+        resolved
+    }
+
+    pub fn set_dispute_bond(&mut self, bond: NearToken) {
+        self.assert_owner();
+        self.dispute_bond = bond;
+    }
+
+    pub fn get_dispute_bond(&self) -> NearToken {
+        self.dispute_bond
+    }
+
+    /// Sends bonds forfeited by rejected disputes to the owner; withdraws everything when `amount` is omitted
+    pub fn withdraw_forfeited_bonds(&mut self, amount: Option<NearToken>) -> Promise {
+        self.assert_owner();
+        let amount = amount.unwrap_or(self.forfeited_bonds);
+        require!(!amount.is_zero() && amount <= self.forfeited_bonds, "Invalid withdrawal amount");
+        self.forfeited_bonds = self.forfeited_bonds.saturating_sub(amount);
+
+        InvestigationEvent::ForfeitedBondsWithdrawn {
+            receiver_id: self.owner_id.to_string(),
+            amount,
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+
+        Promise::new(self.owner_id.clone()).transfer(amount)
+    }
+
+    pub fn get_forfeited_bonds(&self) -> NearToken {
+        self.forfeited_bonds
+    }
+
+    pub fn get_disputes(&self, token_id: TokenId) -> Vec<Dispute> {
+        self.disputes.get(&token_id).unwrap_or_default()
+    }
+
+    pub fn get_open_dispute(&self, token_id: TokenId) -> Option<Dispute> {
+        self.disputes.get(&token_id)
+            .and_then(|disputes| disputes.last().cloned())
+            .filter(|dispute| dispute.resolution.is_none())
+    }
+}
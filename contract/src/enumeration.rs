@@ -7,6 +7,7 @@ use near_sdk::AccountId;
 use near_contract_standards::non_fungible_token::TokenId;
 use near_contract_standards::non_fungible_token::metadata::TokenMetadata;
 use crate::investigation::InvestigationMetadata;
+use crate::privacy::strip_sensitive_fields;
 
 const MAX_LIMIT: u64 = 100;
 
@@ -48,12 +49,21 @@ impl Contract {
         self.tokens.nft_tokens_for_owner(account_id, from_index, Some(limit))
     }
 
+    /// Private cases only expose their public fields and the encrypted payload
     pub fn get_investigation_metadata(&self, token_id: TokenId) -> Option<InvestigationMetadata> {
+        let mut metadata = self.internal_investigation_metadata(&token_id)?;
+        if self.is_private_case(&token_id) {
+            strip_sensitive_fields(&mut metadata);
+        }
+        Some(metadata)
+    }
+
+    pub(crate) fn internal_investigation_metadata(&self, token_id: &TokenId) -> Option<InvestigationMetadata> {
         // First try to get from investigation_data
-        if let Some(metadata) = self.investigation_data.get(&token_id) {
+        if let Some(metadata) = self.investigation_data.get(token_id) {
             return Some(metadata);
         }
-        
+
         // Fallback to token extra data
         if let Some(token) = self.tokens.nft_token(token_id.clone()) {
             if let Some(metadata) = token.metadata {
@@ -1,5 +1,6 @@
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::json_types::U64;
+use near_sdk::NearToken;
 use std::prelude::v1::*;
 
 /// Events emitted by the investigation contract
@@ -58,7 +59,228 @@ pub enum InvestigationEvent {
         context: String,
         error: String,
         timestamp: U64,
-    }
+    },
+    EdgesRecorded {
+        token_id: String,
+        edge_count: u32,
+        timestamp: U64,
+    },
+    EvidenceAdded {
+        token_id: String,
+        added_by: String,
+        evidence_count: u32,
+        timestamp: U64,
+    },
+    AttestationKeyAdded {
+        public_key: String,
+        timestamp: U64,
+    },
+    AttestationKeyRevoked {
+        public_key: String,
+        timestamp: U64,
+    },
+    ResultSubmitted {
+        token_id: String,
+        oracle_id: String,
+        timestamp: U64,
+    },
+    ConsensusReached {
+        token_id: String,
+        agreeing_oracles: u32,
+        timestamp: U64,
+    },
+    ConsensusFailed {
+        token_id: String,
+        submissions: u32,
+        timestamp: U64,
+    },
+    OracleRegistered {
+        oracle_id: String,
+        staked: NearToken,
+        timestamp: U64,
+    },
+    OracleUnstaked {
+        oracle_id: String,
+        amount: NearToken,
+        unbonding_ends_at: U64,
+        timestamp: U64,
+    },
+    OracleStakeWithdrawn {
+        oracle_id: String,
+        amount: NearToken,
+        timestamp: U64,
+    },
+    OracleSlashed {
+        oracle_id: String,
+        amount: NearToken,
+        reason: String,
+        timestamp: U64,
+    },
+    OracleRemoved {
+        oracle_id: String,
+        timestamp: U64,
+    },
+    DisputeOpened {
+        token_id: String,
+        opened_by: String,
+        bond: NearToken,
+        timestamp: U64,
+    },
+    DisputeResolved {
+        token_id: String,
+        outcome: String,
+        timestamp: U64,
+    },
+    InvestigationRedacted {
+        token_id: String,
+        redacted_by: String,
+        timestamp: U64,
+    },
+    JobClaimed {
+        token_id: String,
+        oracle_id: String,
+        worker_id: String,
+        leased_until: U64,
+    },
+    JobReleased {
+        token_id: String,
+        reason: String,
+        timestamp: U64,
+    },
+    BatchStarted {
+        requester: String,
+        token_ids: Vec<String>,
+        timestamp: U64,
+    },
+    WatchlistNotification {
+        token_id: String,
+        target_account: String,
+        status: String,
+        risk_level: String,
+        subscribers: Vec<String>,
+        timestamp: U64,
+    },
+    MonitoringSubscribed {
+        subscription_id: U64,
+        subscriber: String,
+        target_account: String,
+        periods: u32,
+        timestamp: U64,
+    },
+    MonitoringTriggered {
+        subscription_id: U64,
+        token_id: String,
+        periods_remaining: u32,
+        timestamp: U64,
+    },
+    MonitoringCancelled {
+        subscription_id: U64,
+        refund: NearToken,
+        timestamp: U64,
+    },
+    BountyCreated {
+        bounty_id: U64,
+        target_account: String,
+        creator: String,
+        reward: NearToken,
+        deadline: U64,
+    },
+    BountyContribution {
+        bounty_id: U64,
+        contributor: String,
+        amount: NearToken,
+        reward: NearToken,
+    },
+    BountyPaid {
+        bounty_id: U64,
+        token_id: String,
+        recipients: Vec<String>,
+        reward: NearToken,
+    },
+    BountyRefunded {
+        bounty_id: U64,
+        contributors: u32,
+        reward: NearToken,
+    },
+    AnalystRegistered {
+        analyst_id: String,
+        price: NearToken,
+        timestamp: U64,
+    },
+    AnalystResultSubmitted {
+        token_id: String,
+        analyst_id: String,
+        timestamp: U64,
+    },
+    AnalystResultAccepted {
+        token_id: String,
+        analyst_id: String,
+        fee: NearToken,
+        timestamp: U64,
+    },
+    AnalystResultRejected {
+        token_id: String,
+        analyst_id: String,
+        timestamp: U64,
+    },
+    CaseRated {
+        token_id: String,
+        rater: String,
+        rating: u8,
+        edited: bool,
+        timestamp: U64,
+    },
+    CollaboratorAdded {
+        token_id: String,
+        account_id: String,
+        timestamp: U64,
+    },
+    CollaboratorRemoved {
+        token_id: String,
+        account_id: String,
+        timestamp: U64,
+    },
+    NoteAdded {
+        token_id: String,
+        author: String,
+        index: u32,
+        timestamp: U64,
+    },
+    CasesLinked {
+        token_a: String,
+        token_b: String,
+        relation: String,
+        timestamp: U64,
+    },
+    CasesMerged {
+        primary: String,
+        duplicate: String,
+        timestamp: U64,
+    },
+    ContractMetadataUpdated {
+        name: String,
+        symbol: String,
+        timestamp: U64,
+    },
+    OracleReinstated {
+        oracle_id: String,
+        timestamp: U64,
+    },
+    SlashedBalanceWithdrawn {
+        receiver_id: String,
+        amount: NearToken,
+        timestamp: U64,
+    },
+    MonitoringFeesWithdrawn {
+        receiver_id: String,
+        amount: NearToken,
+        timestamp: U64,
+    },
+    ForfeitedBondsWithdrawn {
+        receiver_id: String,
+        amount: NearToken,
+        timestamp: U64,
+    },
 }
 
 impl InvestigationEvent {
@@ -0,0 +1,162 @@
+use std::prelude::v1::*;
+use crate::*;
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// Maximum number of evidence entries attached to a single case
+pub const MAX_EVIDENCE_PER_CASE: usize = 50;
+pub const MAX_EVIDENCE_URI_LENGTH: usize = 512;
+pub const MAX_EVIDENCE_DESCRIPTION_LENGTH: usize = 1_000;
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum EvidenceKind {
+    TransactionHash,
+    CsvExport,
+    Screenshot,
+    Document,
+    Other,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Evidence {
+    pub kind: EvidenceKind,
+    pub uri: String,
+    /// Hex encoded sha256 of the referenced content
+    pub sha256: String,
+    pub description: Option<String>,
+    pub added_by: AccountId,
+    pub added_at: U64,
+}
+
+/// Canonical evidence manifest; its sha256 is kept in the case's `EvidenceManifestRecord`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EvidenceManifest {
+    pub token_id: TokenId,
+    pub evidence: Vec<Evidence>,
+}
+
+/// Where the evidence manifest is pinned and the hash of its current contents.
+///
+/// The manifest is deliberately not stored in the token's NEP-177 `reference`/`reference_hash`:
+/// those carry the full report submitted through `MetadataUpdate.reference`, and a single
+/// field cannot point at both without each update overwriting the other. Clients find the
+/// manifest through `get_evidence_manifest_record` instead.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EvidenceManifestRecord {
+    pub uri: Option<String>,
+    /// Base64 encoded sha256 of `get_evidence_manifest`, recomputed on every evidence change
+    pub hash: Base64VecU8,
+    pub updated_at: U64,
+}
+
+pub fn is_valid_sha256_hex(hash: &str) -> bool {
+    hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Attaches evidence to a case and refreshes the manifest hash. `manifest_uri`, when
+    /// given, replaces the recorded location of the pinned manifest.
+    pub fn add_evidence(
+        &mut self,
+        token_id: TokenId,
+        kind: EvidenceKind,
+        uri: String,
+        sha256: String,
+        description: Option<String>,
+        manifest_uri: Option<String>,
+    ) -> u32 {
+        let investigation = self.get_investigation_metadata(token_id.clone())
+            .unwrap_or_else(|| env::panic_str("Investigation not found"));
+
+        let caller = env::predecessor_account_id();
+        require!(
+            self.is_oracle(&caller)
+                || self.moderators.contains(&caller)
+                || caller == investigation.requester,
+            "Only the oracle, a moderator or the requester can add evidence"
+        );
+        require!(
+            !uri.is_empty() && uri.len() <= MAX_EVIDENCE_URI_LENGTH,
+            "Invalid evidence URI"
+        );
+        require!(is_valid_sha256_hex(&sha256), "sha256 must be 64 hex characters");
+        if let Some(description) = &description {
+            require!(
+                description.len() <= MAX_EVIDENCE_DESCRIPTION_LENGTH,
+                "Evidence description too long"
+            );
+        }
+
+        let mut evidence = self.evidence.get(&token_id).unwrap_or_default();
+        require!(
+            evidence.len() < MAX_EVIDENCE_PER_CASE,
+            format!("Cannot attach more than {} evidence entries", MAX_EVIDENCE_PER_CASE)
+        );
+        evidence.push(Evidence {
+            kind,
+            uri,
+            sha256: sha256.to_lowercase(),
+            description,
+            added_by: caller.clone(),
+            added_at: U64(env::block_timestamp()),
+        });
+        self.evidence.insert(&token_id, &evidence);
+
+        if let Some(manifest_uri) = &manifest_uri {
+            require!(
+                !manifest_uri.is_empty() && manifest_uri.len() <= MAX_EVIDENCE_URI_LENGTH,
+                "Invalid manifest URI"
+            );
+        }
+        self.internal_refresh_evidence_manifest(&token_id, manifest_uri);
+
+        InvestigationEvent::EvidenceAdded {
+            token_id,
+            added_by: caller.to_string(),
+            evidence_count: evidence.len() as u32,
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+
+        evidence.len() as u32
+    }
+
+    pub fn get_evidence(&self, token_id: TokenId) -> Vec<Evidence> {
+        self.evidence.get(&token_id).unwrap_or_default()
+    }
+
+    pub fn get_evidence_manifest(&self, token_id: TokenId) -> EvidenceManifest {
+        EvidenceManifest {
+            evidence: self.get_evidence(token_id.clone()),
+            token_id,
+        }
+    }
+
+    pub fn get_evidence_manifest_record(&self, token_id: TokenId) -> Option<EvidenceManifestRecord> {
+        self.evidence_manifests.get(&token_id)
+    }
+}
+
+impl Contract {
+    /// Base64 encoded sha256 of the canonical (serde JSON) evidence manifest
+    pub(crate) fn evidence_manifest_hash(&self, token_id: &TokenId) -> Base64VecU8 {
+        let manifest = self.get_evidence_manifest(token_id.clone());
+        let bytes = serde_json::to_vec(&manifest).unwrap_or_default();
+        Base64VecU8::from(env::sha256(&bytes))
+    }
+
+    /// Re-hashes the manifest after its evidence changed, keeping the pinned URI
+    /// unless a new one is given
+    pub(crate) fn internal_refresh_evidence_manifest(&mut self, token_id: &TokenId, uri: Option<String>) {
+        let uri = uri.or_else(|| self.evidence_manifests.get(token_id).and_then(|record| record.uri));
+        self.evidence_manifests.insert(token_id, &EvidenceManifestRecord {
+            uri,
+            hash: self.evidence_manifest_hash(token_id),
+            updated_at: U64(env::block_timestamp()),
+        });
+    }
+}
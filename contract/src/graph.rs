@@ -0,0 +1,187 @@
+use std::prelude::v1::*;
+use std::collections::HashSet;
+use crate::*;
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// Maximum number of edges that can be recorded in a single call
+pub const MAX_EDGES_PER_CALL: usize = 50;
+/// Maximum number of outgoing (or incoming) edges tracked per account
+pub const MAX_EDGES_PER_ACCOUNT: usize = 500;
+/// Upper bound on edges inspected by a single graph view call
+pub const MAX_GRAPH_SCAN: usize = 1_000;
+const MAX_GRAPH_LIMIT: u64 = 100;
+const MAX_NEIGHBOR_DEPTH: u8 = 2;
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum RelationKind {
+    Transfer,
+    Counterparty,
+    Funding,
+    ContractCall,
+    SharedController,
+    Other,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountEdge {
+    pub from_account: AccountId,
+    pub to_account: AccountId,
+    pub relation_kind: RelationKind,
+    pub weight: u32,
+    pub evidence_token_id: TokenId,
+    pub recorded_at: U64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EdgeInput {
+    pub from_account: AccountId,
+    pub to_account: AccountId,
+    pub relation_kind: RelationKind,
+    pub weight: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountNeighbor {
+    pub account_id: AccountId,
+    pub depth: u8,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Records relationship edges discovered while investigating `token_id`.
+    /// Re-recording an existing `(from, to, kind)` edge refreshes its weight and evidence.
+    pub fn record_edges(&mut self, token_id: TokenId, edges: Vec<EdgeInput>) -> u32 {
+        self.assert_oracle();
+        require!(
+            self.investigation_status.get(&token_id).is_some(),
+            "Investigation not found"
+        );
+        // The edge graph is public, so it would reveal who a private target deals with
+        require!(!self.is_private_case(&token_id), "Cannot record edges for a private investigation");
+        require!(!edges.is_empty(), "No edges provided");
+        require!(
+            edges.len() <= MAX_EDGES_PER_CALL,
+            format!("Cannot record more than {} edges per call", MAX_EDGES_PER_CALL)
+        );
+
+        for input in edges.iter() {
+            require!(input.from_account != input.to_account, "Self-referencing edges are not allowed");
+            let edge = AccountEdge {
+                from_account: input.from_account.clone(),
+                to_account: input.to_account.clone(),
+                relation_kind: input.relation_kind.clone(),
+                weight: input.weight,
+                evidence_token_id: token_id.clone(),
+                recorded_at: U64(env::block_timestamp()),
+            };
+
+            let mut outgoing = self.edges_from.get(&edge.from_account).unwrap_or_default();
+            let existing = outgoing.iter().copied().find(|index| {
+                self.edges.get(*index).is_some_and(|stored| {
+                    stored.to_account == edge.to_account && stored.relation_kind == edge.relation_kind
+                })
+            });
+
+            match existing {
+                Some(index) => {
+                    self.edges.replace(index, &edge);
+                }
+                None => {
+                    let mut incoming = self.edges_to.get(&edge.to_account).unwrap_or_default();
+                    require!(
+                        outgoing.len() < MAX_EDGES_PER_ACCOUNT && incoming.len() < MAX_EDGES_PER_ACCOUNT,
+                        "Edge limit reached for account"
+                    );
+                    let index = self.edges.len();
+                    self.edges.push(&edge);
+                    outgoing.push(index);
+                    incoming.push(index);
+                    self.edges_from.insert(&edge.from_account, &outgoing);
+                    self.edges_to.insert(&edge.to_account, &incoming);
+                }
+            }
+        }
+
+        InvestigationEvent::EdgesRecorded {
+            token_id,
+            edge_count: edges.len() as u32,
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+
+        edges.len() as u32
+    }
+
+    pub fn get_edges_from(&self, account_id: AccountId, from_index: Option<u64>, limit: Option<u64>) -> Vec<AccountEdge> {
+        let indices = self.edges_from.get(&account_id).unwrap_or_default();
+        self.paginate_edges(&indices, from_index, limit)
+    }
+
+    pub fn get_edges_to(&self, account_id: AccountId, from_index: Option<u64>, limit: Option<u64>) -> Vec<AccountEdge> {
+        let indices = self.edges_to.get(&account_id).unwrap_or_default();
+        self.paginate_edges(&indices, from_index, limit)
+    }
+
+    /// Breadth-first walk over incoming and outgoing edges, up to `depth` hops (max 2).
+    pub fn get_neighbors(
+        &self,
+        account_id: AccountId,
+        depth: Option<u8>,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<AccountNeighbor> {
+        let depth = depth.unwrap_or(1);
+        require!(
+            (1..=MAX_NEIGHBOR_DEPTH).contains(&depth),
+            format!("Depth must be between 1 and {}", MAX_NEIGHBOR_DEPTH)
+        );
+
+        let mut visited: HashSet<AccountId> = HashSet::new();
+        visited.insert(account_id.clone());
+        let mut neighbors: Vec<AccountNeighbor> = Vec::new();
+        let mut frontier = vec![account_id];
+        let mut scanned = 0usize;
+
+        'walk: for level in 1..=depth {
+            let mut next = Vec::new();
+            for account in frontier.iter() {
+                let mut indices = self.edges_from.get(account).unwrap_or_default();
+                indices.extend(self.edges_to.get(account).unwrap_or_default());
+                for index in indices {
+                    if scanned >= MAX_GRAPH_SCAN {
+                        break 'walk;
+                    }
+                    scanned += 1;
+                    if let Some(edge) = self.edges.get(index) {
+                        let other = if &edge.from_account == account { edge.to_account } else { edge.from_account };
+                        if visited.insert(other.clone()) {
+                            neighbors.push(AccountNeighbor { account_id: other.clone(), depth: level });
+                            next.push(other);
+                        }
+                    }
+                }
+            }
+            frontier = next;
+        }
+
+        let limit = limit.unwrap_or(MAX_GRAPH_LIMIT).min(MAX_GRAPH_LIMIT) as usize;
+        neighbors
+            .into_iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit)
+            .collect()
+    }
+
+    fn paginate_edges(&self, indices: &[u64], from_index: Option<u64>, limit: Option<u64>) -> Vec<AccountEdge> {
+        let limit = limit.unwrap_or(MAX_GRAPH_LIMIT).min(MAX_GRAPH_LIMIT) as usize;
+        indices
+            .iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit)
+            .filter_map(|index| self.edges.get(*index))
+            .collect()
+    }
+}
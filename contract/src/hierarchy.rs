@@ -0,0 +1,132 @@
+use std::prelude::v1::*;
+use crate::*;
+use near_sdk::serde::{Deserialize, Serialize};
+
+const MAX_HIERARCHY_LIMIT: u64 = 100;
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub enum AccountKind {
+    /// Human readable account, e.g. `foo.bar.near`
+    #[default]
+    Named,
+    /// 64 hex character account derived from an ed25519 key
+    Implicit,
+    /// `0x` followed by 40 hex characters, derived from an Ethereum address
+    EvmImplicit,
+}
+
+impl AccountKind {
+    pub fn of(account_id: &AccountId) -> Self {
+        let id = account_id.as_str();
+        let is_hex = |s: &str| s.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'));
+        if id.len() == 64 && is_hex(id) {
+            AccountKind::Implicit
+        } else if id.len() == 42 && id.starts_with("0x") && is_hex(&id[2..]) {
+            AccountKind::EvmImplicit
+        } else {
+            AccountKind::Named
+        }
+    }
+}
+
+/// Accounts able to create `account_id`, nearest first. Top-level accounts such as
+/// `near` are registrars rather than controllers and are left out.
+pub fn parent_accounts(account_id: &AccountId) -> Vec<AccountId> {
+    let id = account_id.as_str();
+    id.match_indices('.')
+        .map(|(index, _)| &id[index + 1..])
+        .filter(|parent| parent.contains('.'))
+        .filter_map(|parent| parent.parse().ok())
+        .collect()
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Cases whose target is a sub-account (at any depth) of `parent_account`
+    pub fn get_cases_under_parent(&self, parent_account: AccountId, from_index: Option<u64>, limit: Option<u64>) -> Vec<TokenId> {
+        let limit = limit.unwrap_or(MAX_HIERARCHY_LIMIT).min(MAX_HIERARCHY_LIMIT) as usize;
+        self.cases_by_parent
+            .iter_from((parent_account.clone(), String::new()))
+            .take_while(|((parent, _), _)| parent == &parent_account)
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit)
+            .map(|((_, token_id), _)| token_id)
+            .collect()
+    }
+
+    /// When enabled, a sub-account's case inherits the highest risk level found on
+    /// its parents' completed cases
+    pub fn set_label_rolldown(&mut self, enabled: bool) {
+        self.assert_owner();
+        self.label_rolldown = enabled;
+    }
+
+    pub fn get_label_rolldown(&self) -> bool {
+        self.label_rolldown
+    }
+
+    /// Re-applies the roll-down policy to existing cases under `parent_account`, e.g. after
+    /// the parent was flagged. Callable by anyone; returns the number of cases updated.
+    pub fn roll_down_labels(&mut self, parent_account: AccountId, from_index: Option<u64>, limit: Option<u64>) -> u32 {
+        require!(self.label_rolldown, "Label roll-down is not enabled");
+        let mut updated = 0;
+        for token_id in self.get_cases_under_parent(parent_account, from_index, limit) {
+            if let Some(mut metadata) = self.investigation_data.get(&token_id) {
+                if self.apply_label_rolldown(&mut metadata) {
+                    self.investigation_data.insert(&token_id, &metadata);
+                    self.internal_notify_watchers(&token_id, &metadata);
+                    self.internal_refresh_artwork(&token_id, &metadata);
+                    updated += 1;
+                }
+            }
+        }
+        updated
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_index_by_parent(&mut self, token_id: &TokenId, target_account: &AccountId) {
+        for parent in parent_accounts(target_account) {
+            self.cases_by_parent.insert(&(parent, token_id.clone()), target_account);
+        }
+    }
+
+    pub(crate) fn internal_unindex_by_parent(&mut self, token_id: &TokenId, target_account: &AccountId) {
+        for parent in parent_accounts(target_account) {
+            self.cases_by_parent.remove(&(parent, token_id.clone()));
+        }
+    }
+
+    /// Highest risk level on a completed parent case, with the parent it came from
+    pub(crate) fn inherited_risk(&self, target_account: &AccountId) -> Option<(RiskLevel, AccountId)> {
+        parent_accounts(target_account)
+            .into_iter()
+            .filter_map(|parent| {
+                let token_id = self.investigated_accounts.get(&parent)?;
+                if self.investigation_status.get(&token_id) != Some(InvestigationStatus::Completed) {
+                    return None;
+                }
+                self.investigation_data.get(&token_id).map(|metadata| (metadata.risk_level, parent))
+            })
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+    }
+
+    /// Refreshes the account classification and, when the policy is on, raises the risk
+    /// level to the parents'. Returns whether the risk level changed.
+    pub(crate) fn apply_label_rolldown(&self, metadata: &mut InvestigationMetadata) -> bool {
+        metadata.account_kind = AccountKind::of(&metadata.target_account);
+        metadata.parent_accounts = parent_accounts(&metadata.target_account);
+        if !self.label_rolldown {
+            return false;
+        }
+        match self.inherited_risk(&metadata.target_account) {
+            Some((risk_level, parent)) if risk_level > metadata.risk_level => {
+                metadata.risk_level = risk_level;
+                metadata.risk_inherited_from = Some(parent);
+                true
+            }
+            _ => false,
+        }
+    }
+}
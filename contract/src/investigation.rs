@@ -1,8 +1,9 @@
 use std::prelude::v1::*;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::serde::{Deserialize, Serialize};
-use near_sdk::{AccountId, json_types::U64, env};
+use near_sdk::{AccountId, json_types::{Base64VecU8, U64}, env};
 use crate::webhook_mappings::WebhookType;
+use crate::hierarchy::{parent_accounts, AccountKind};
 
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(crate = "near_sdk::serde")]
@@ -10,7 +11,43 @@ pub enum InvestigationStatus {
     Pending,
     Processing,
     Completed,
-    Failed
+    Failed,
+    Disputed,
+    /// Folded into another case by `merge_cases`
+    Merged,
+    /// An analyst's completed result waiting for the requester to accept it
+    UnderReview,
+    /// Oracle submissions could not reach the consensus quorum
+    NoConsensus,
+}
+
+impl InvestigationStatus {
+    /// Whether a new run can be requested for a case in this status
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, InvestigationStatus::Failed | InvestigationStatus::NoConsensus)
+    }
+
+    /// Statuses only the contract's own flows (disputes, merges, review, consensus) may set
+    pub fn is_contract_managed(&self) -> bool {
+        matches!(
+            self,
+            InvestigationStatus::Disputed
+                | InvestigationStatus::Merged
+                | InvestigationStatus::UnderReview
+                | InvestigationStatus::NoConsensus
+        )
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub enum RiskLevel {
+    #[default]
+    Unknown,
+    Low,
+    Medium,
+    High,
+    Critical,
 }
 
 // Add From implementation for WebhookType
@@ -25,6 +62,64 @@ impl From<WebhookType> for InvestigationStatus {
     }
 }
 
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Visibility {
+    #[default]
+    Public,
+    Private,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub enum InvestigationTier {
+    #[default]
+    Standard,
+    Priority,
+    DeepDive,
+}
+
+impl InvestigationOptions {
+    pub fn assert_valid(&self) {
+        if self.visibility == Visibility::Private {
+            near_sdk::require!(
+                self.encryption_public_key.as_ref().is_some_and(|key| key.0.len() == 32),
+                "Private investigations require a 32 byte encryption public key"
+            );
+        }
+    }
+}
+
+impl InvestigationTier {
+    /// Lower ranks are claimed first from the job queue
+    pub fn queue_rank(&self) -> u8 {
+        match self {
+            InvestigationTier::Priority => 0,
+            InvestigationTier::DeepDive => 1,
+            InvestigationTier::Standard => 2,
+        }
+    }
+}
+
+/// Optional settings for `start_investigation`
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct InvestigationOptions {
+    #[serde(default)]
+    pub visibility: Visibility,
+    /// Requester's 32 byte public key; required for private investigations
+    #[serde(default)]
+    pub encryption_public_key: Option<Base64VecU8>,
+    /// How long (in nanoseconds) the case file is retained before it can be archived
+    #[serde(default)]
+    pub retention_period_ns: Option<U64>,
+    #[serde(default)]
+    pub tier: InvestigationTier,
+    /// Marketplace analyst to handle the case instead of the pipeline
+    #[serde(default)]
+    pub analyst: Option<AccountId>,
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
 #[serde(crate = "near_sdk::serde")]
 pub struct AnalysisSummary {
@@ -56,6 +151,57 @@ pub struct InvestigationMetadata {
     pub near_balance: String,            // Keep NEAR balance separate
     pub is_bot: bool,                    // Keep bot detection
     pub last_updated: U64,              // For frontend compatibility
+    #[serde(default)]
+    pub risk_level: RiskLevel,
+    #[serde(default)]
+    pub redacted: bool,
+    #[serde(default)]
+    pub visibility: Visibility,
+    /// Summary and financials encrypted to the requester's key (private cases only)
+    #[serde(default)]
+    pub encrypted_payload: Option<Base64VecU8>,
+    #[serde(default)]
+    pub tier: InvestigationTier,
+    #[serde(default)]
+    pub account_kind: AccountKind,
+    /// Accounts that can create `target_account`, nearest first
+    #[serde(default)]
+    pub parent_accounts: Vec<AccountId>,
+    /// Parent whose risk level was rolled down onto this case
+    #[serde(default)]
+    pub risk_inherited_from: Option<AccountId>,
+}
+
+/// `InvestigationMetadata` as stored by version 1, before the risk and privacy fields
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct OldInvestigationMetadata {
+    pub case_number: u64,
+    pub target_account: AccountId,
+    pub requester: AccountId,
+    pub investigation_date: U64,
+    pub status: InvestigationStatus,
+    pub summary: Option<String>,
+    pub transaction_count: u64,
+    pub total_value_usd: String,
+    pub near_balance: String,
+    pub is_bot: bool,
+    pub last_updated: U64,
+}
+
+impl From<OldInvestigationMetadata> for InvestigationMetadata {
+    fn from(old: OldInvestigationMetadata) -> Self {
+        Self {
+            investigation_date: old.investigation_date,
+            status: old.status,
+            summary: old.summary,
+            transaction_count: old.transaction_count,
+            total_value_usd: old.total_value_usd,
+            near_balance: old.near_balance,
+            is_bot: old.is_bot,
+            last_updated: old.last_updated,
+            ..Self::new(old.case_number, old.target_account, old.requester)
+        }
+    }
 }
 
 impl InvestigationMetadata {
@@ -66,6 +212,8 @@ impl InvestigationMetadata {
     ) -> Self {
         Self {
             case_number,
+            account_kind: AccountKind::of(&target_account),
+            parent_accounts: parent_accounts(&target_account),
             target_account,
             requester,
             investigation_date: U64(env::block_timestamp()),
@@ -76,9 +224,26 @@ impl InvestigationMetadata {
             total_value_usd: "0".to_string(),
             near_balance: "0".to_string(),
             is_bot: false,
+            risk_level: RiskLevel::Unknown,
+            redacted: false,
+            visibility: Visibility::Public,
+            encrypted_payload: None,
+            tier: InvestigationTier::Standard,
+            risk_inherited_from: None,
         }
     }
 
+    /// Copies the fields fixed when the case was opened from `stored`, so a submitted
+    /// result cannot change who requested the case or what it is about
+    pub fn keep_case_identity(&mut self, stored: &InvestigationMetadata) {
+        self.case_number = stored.case_number;
+        self.target_account = stored.target_account.clone();
+        self.requester = stored.requester.clone();
+        self.investigation_date = stored.investigation_date;
+        self.visibility = stored.visibility.clone();
+        self.tier = stored.tier.clone();
+    }
+
     pub fn update_from_webhook(&mut self, webhook_data: &serde_json::Value) {
         // Update status
         if let Some(status) = webhook_data.get("status").and_then(|v| v.as_str()) {
@@ -113,8 +278,25 @@ impl InvestigationMetadata {
             if let Some(is_bot) = result.get("isBot").and_then(|v| v.as_bool()) {
                 self.is_bot = is_bot;
             }
+            if let Some(risk_level) = result.get("riskLevel").and_then(|v| v.as_str()) {
+                self.risk_level = match risk_level {
+                    "low" => RiskLevel::Low,
+                    "medium" => RiskLevel::Medium,
+                    "high" => RiskLevel::High,
+                    "critical" => RiskLevel::Critical,
+                    _ => RiskLevel::Unknown,
+                };
+            }
         }
 
         self.last_updated = U64(env::block_timestamp());
     }
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct InvestigationResponse {
+    pub request_id: String,
+    pub status: InvestigationStatus,
+    pub message: Option<String>,
+}
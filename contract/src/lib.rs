@@ -2,8 +2,8 @@
 use std::prelude::v1::*;
 
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::{LazyOption, LookupMap, UnorderedMap};
-use near_sdk::json_types::U64;
+use near_sdk::collections::{LazyOption, LookupMap, TreeMap, UnorderedMap, UnorderedSet, Vector};
+use near_sdk::json_types::{Base64VecU8, U64};
 use near_sdk::{
     env, 
     near_bindgen, 
@@ -12,6 +12,7 @@ use near_sdk::{
     PanicOnDefault,
     PromiseOrValue,
     Promise,
+    PublicKey,
     require,
     NearToken,
 };
@@ -24,7 +25,6 @@ use near_contract_standards::non_fungible_token::{
 use near_contract_standards::non_fungible_token::metadata::{
     NFTContractMetadata,
     TokenMetadata,
-    NFT_METADATA_SPEC,
 };
 use near_contract_standards::non_fungible_token::core::NonFungibleTokenCore;
 
@@ -34,25 +34,81 @@ mod investigation;
 mod enumeration;
 mod events;
 mod webhook_mappings;
+mod graph;
+mod roles;
+mod evidence;
+mod attestation;
+mod consensus;
+mod staking;
+mod disputes;
+mod redaction;
+mod privacy;
+mod archive;
+mod timeouts;
+mod queue;
+mod tiers;
+mod batch;
+mod watchlist;
+mod monitoring;
+mod bounties;
+mod analysts;
+mod reputation;
+mod ratings;
+mod notes;
+mod links;
+mod hierarchy;
+mod artwork;
 
 // Re-exports with explicit types
-pub use crate::metadata::{MetadataUpdate};
+pub use crate::metadata::{MetadataUpdate, default_contract_metadata, assert_valid_contract_metadata};
 pub use crate::investigation::{
     InvestigationMetadata, 
     InvestigationResponse, 
     InvestigationStatus,
+    InvestigationOptions,
+    InvestigationTier,
+    RiskLevel,
+    Visibility,
     FinancialSummary,
     AnalysisSummary
 };
-pub use crate::events::InvestigationEvent;
-pub use crate::webhook_mappings::WebhookType;
+pub use crate::events::{InvestigationEvent, NftMintLog};
+pub use crate::webhook_mappings::{WebhookResponse, WebhookType};
+pub use crate::graph::{AccountEdge, AccountNeighbor, EdgeInput, RelationKind};
+pub use crate::evidence::{Evidence, EvidenceKind, EvidenceManifest, EvidenceManifestRecord};
+pub use crate::attestation::{Attestation, AttestationKey, SignedAttestation, MAX_ATTESTATIONS_PER_CASE};
+pub use crate::consensus::{ConsensusConfig, OracleSubmission};
+pub use crate::staking::{OracleStake, StakingConfig, DEFAULT_DISPUTE_SLASH, DEFAULT_MIN_ORACLE_STAKE};
+pub use crate::disputes::{Dispute, DisputeOutcome, DisputeResolution, DEFAULT_DISPUTE_BOND};
+pub use crate::redaction::{RedactableField, RedactionRecord};
+pub use crate::privacy::PublicInvestigationView;
+pub use crate::timeouts::DEFAULT_INVESTIGATION_TIMEOUT_NS;
+pub use crate::queue::{JobAssignment, JobLease, DEFAULT_JOB_LEASE_NS};
+pub use crate::tiers::TierConfig;
+pub use crate::batch::MAX_BATCH_SIZE;
+pub use crate::monitoring::{MonitoringSubscription, MIN_MONITORING_INTERVAL_NS};
+pub use crate::bounties::{Bounty, BountyContribution, BountyStatus, CaseResolution, BOUNTY_DISPUTE_WINDOW_NS};
+pub use crate::analysts::{AnalystAssignment, AnalystProfile, AssignmentStatus, ANALYST_REVIEW_PERIOD_NS};
+pub use crate::reputation::{Reputation, ReputationView};
+pub use crate::ratings::{CaseRating, RatingStats, RATING_EDIT_WINDOW_NS};
+pub use crate::notes::{CaseNote, NoteVisibility};
+pub use crate::links::{CaseLink, CaseRelation};
+pub use crate::hierarchy::{parent_accounts, AccountKind};
+pub use crate::artwork::{case_artwork, render_case_svg};
+use crate::investigation::OldInvestigationMetadata;
+use crate::tiers::default_tiers;
+use crate::privacy::{assert_private_update, strip_sensitive_fields, PRIVATE_CASE_DESCRIPTION};
 
 // Constants
 pub const NFT_STANDARD_NAME: &str = "nep171";
 pub const DEFAULT_ICON_URL: &str = "https://gateway.pinata.cloud/ipfs/QmYkT5eNLePKnvw9vLXNdLxFynp8amKUPaPZ74LhQxxdpu";
+pub const DEFAULT_BASE_URI: &str = "https://gateway.pinata.cloud/ipfs";
+pub const DEFAULT_CONTRACT_NAME: &str = "Chain Sleuth";
+pub const DEFAULT_CONTRACT_SYMBOL: &str = "CSI";
 pub const DEFAULT_NFT_IMAGE_URL: &str = "https://gateway.pinata.cloud/ipfs/QmSNycrd5gWH7QAFKBVvKaT58c5S6B1tq9ScHP7thxvLWM";
+pub const INVESTIGATION_FEE: NearToken = NearToken::from_millinear(10); // 0.01 NEAR
 
-#[derive(BorshSerialize)]
+#[derive(BorshSerialize, BorshStorageKey)]
 pub enum StorageKey {
     NonFungibleToken,
     Metadata,
@@ -63,11 +119,73 @@ pub enum StorageKey {
     InvestigationStatus,
     InvestigationData,
     FailedMints,
+    Edges,
+    EdgesFrom,
+    EdgesTo,
+    Moderators,
+    Evidence,
+    AttestationKeys,
+    Attestations,
+    Oracles,
+    OracleSubmissions,
+    OracleStakes,
+    Disputes,
+    Redactions,
+    OptedOutAccounts,
+    PrivateCases,
+    CasePayments,
+    RetryCounts,
+    JobQueue,
+    JobPositions,
+    JobLeases,
+    CaseTiers,
+    Watchlists,
+    Watchers,
+    Subscriptions,
+    SubscriptionsByAccount,
+    Bounties,
+    OpenBounties,
+    CaseResolutions,
+    Analysts,
+    AnalystAssignments,
+    Reputations,
+    ReputationRanking,
+    CaseRatings,
+    CaseNotes,
+    Collaborators,
+    CaseLinks,
+    MergedCases,
+    CasesByParent,
+    RemovedOracles,
+    SubscriptionsDue,
+    LeasesByExpiry,
+    CasePayers,
+    EvidenceManifests,
+    BountiesByTarget,
+    CaseNoteCounts,
+}
+
+/// Version 1 layout, as deployed before the case management features were added
+#[derive(BorshDeserialize)]
+#[cfg_attr(test, derive(BorshSerialize))]
+#[borsh(crate = "near_sdk::borsh")]
+struct OldState {
+    version: u32,
+    owner_id: AccountId,
+    tokens: NonFungibleToken,
+    metadata: LazyOption<NFTContractMetadata>,
+    case_number_counter: u64,
+    investigated_accounts: LookupMap<AccountId, TokenId>,
+    investigation_status: UnorderedMap<TokenId, InvestigationStatus>,
+    investigation_data: UnorderedMap<TokenId, OldInvestigationMetadata>,
+    failed_mints: UnorderedMap<TokenId, String>,
 }
 
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct Contract {
+    pub version: u32,
+    pub owner_id: AccountId,
     pub tokens: NonFungibleToken,
     pub metadata: LazyOption<NFTContractMetadata>,
     pub case_number_counter: u64,
@@ -75,13 +193,78 @@ pub struct Contract {
     pub investigation_status: UnorderedMap<TokenId, InvestigationStatus>,
     pub investigation_data: UnorderedMap<TokenId, InvestigationMetadata>,
     pub failed_mints: UnorderedMap<TokenId, String>,
+    pub edges: Vector<AccountEdge>,
+    pub edges_from: LookupMap<AccountId, Vec<u64>>,
+    pub edges_to: LookupMap<AccountId, Vec<u64>>,
+    pub moderators: UnorderedSet<AccountId>,
+    pub evidence: LookupMap<TokenId, Vec<Evidence>>,
+    pub max_extra_size: Option<u32>,
+    pub attestation_keys: UnorderedMap<PublicKey, AttestationKey>,
+    pub attestations: LookupMap<TokenId, Vec<Attestation>>,
+    pub require_attestation: bool,
+    pub oracles: UnorderedSet<AccountId>,
+    pub oracle_submissions: LookupMap<TokenId, Vec<OracleSubmission>>,
+    pub consensus_config: ConsensusConfig,
+    pub oracle_stakes: LookupMap<AccountId, OracleStake>,
+    pub staking_config: StakingConfig,
+    pub slashed_balance: NearToken,
+    pub disputes: LookupMap<TokenId, Vec<Dispute>>,
+    pub dispute_bond: NearToken,
+    pub forfeited_bonds: NearToken,
+    pub redactions: LookupMap<TokenId, Vec<RedactionRecord>>,
+    pub opted_out_accounts: UnorderedSet<AccountId>,
+    pub private_cases: LookupMap<TokenId, Base64VecU8>,
+    pub case_payments: LookupMap<TokenId, NearToken>,
+    pub retry_counts: LookupMap<TokenId, u32>,
+    pub investigation_timeout_ns: U64,
+    pub job_queue: TreeMap<(u8, u64), TokenId>,
+    pub job_positions: LookupMap<TokenId, (u8, u64)>,
+    pub job_leases: UnorderedMap<TokenId, JobLease>,
+    pub next_job_position: u64,
+    pub job_lease_duration_ns: U64,
+    pub case_tiers: LookupMap<TokenId, InvestigationTier>,
+    pub tiers: Vec<TierConfig>,
+    pub watchlists: LookupMap<AccountId, Vec<AccountId>>,
+    pub watchers: LookupMap<AccountId, Vec<AccountId>>,
+    pub subscriptions: UnorderedMap<u64, MonitoringSubscription>,
+    pub subscriptions_by_account: LookupMap<AccountId, Vec<u64>>,
+    pub next_subscription_id: u64,
+    pub collected_monitoring_fees: NearToken,
+    pub bounties: UnorderedMap<u64, Bounty>,
+    pub open_bounties: UnorderedSet<u64>,
+    pub next_bounty_id: u64,
+    pub case_resolutions: LookupMap<TokenId, CaseResolution>,
+    pub analysts: UnorderedMap<AccountId, AnalystProfile>,
+    pub analyst_assignments: LookupMap<TokenId, AnalystAssignment>,
+    pub reputations: LookupMap<AccountId, Reputation>,
+    pub reputation_ranking: TreeMap<(u32, AccountId), u32>,
+    pub case_ratings: LookupMap<TokenId, CaseRating>,
+    pub rating_stats: RatingStats,
+    /// Notes keyed by case and position, so appending never rewrites earlier notes
+    pub case_notes: LookupMap<(TokenId, u32), CaseNote>,
+    pub case_note_counts: LookupMap<TokenId, u32>,
+    pub collaborators: LookupMap<TokenId, Vec<AccountId>>,
+    pub case_links: LookupMap<TokenId, Vec<CaseLink>>,
+    pub merged_cases: LookupMap<TokenId, Vec<TokenId>>,
+    pub cases_by_parent: TreeMap<(AccountId, TokenId), AccountId>,
+    pub label_rolldown: bool,
+    pub removed_oracles: UnorderedSet<AccountId>,
+    pub subscriptions_due: TreeMap<(u64, u64), ()>,
+    pub leases_by_expiry: TreeMap<(u64, TokenId), ()>,
+    pub case_payers: LookupMap<TokenId, AccountId>,
+    pub evidence_manifests: LookupMap<TokenId, EvidenceManifestRecord>,
+    pub bounties_by_target: LookupMap<AccountId, Vec<u64>>,
 }
 
 #[near_bindgen]
 impl Contract {
+    /// `metadata` defaults to `default_contract_metadata()` when omitted
     #[init]
-    pub fn new(owner_id: AccountId) -> Self {
-        Self {
+    pub fn new(owner_id: AccountId, metadata: Option<NFTContractMetadata>) -> Self {
+        let metadata = metadata.unwrap_or_else(default_contract_metadata);
+        assert_valid_contract_metadata(&metadata);
+
+        let contract = Self {
             tokens: NonFungibleToken::new(
                 StorageKey::NonFungibleToken,
                 owner_id.clone(),
@@ -89,118 +272,182 @@ impl Contract {
                 Some(StorageKey::Enumeration),
                 Some(StorageKey::Approval),
             ),
-            metadata: LazyOption::new(StorageKey::Metadata, None),
+            metadata: LazyOption::new(StorageKey::Metadata, Some(&metadata)),
             owner_id,
+            version: 2,
             case_number_counter: 0,
             investigated_accounts: LookupMap::new(StorageKey::InvestigatedAccounts),
             investigation_status: UnorderedMap::new(StorageKey::InvestigationStatus),
             investigation_data: UnorderedMap::new(StorageKey::InvestigationData),
             failed_mints: UnorderedMap::new(StorageKey::FailedMints),
-        }
+            edges: Vector::new(StorageKey::Edges),
+            edges_from: LookupMap::new(StorageKey::EdgesFrom),
+            edges_to: LookupMap::new(StorageKey::EdgesTo),
+            moderators: UnorderedSet::new(StorageKey::Moderators),
+            evidence: LookupMap::new(StorageKey::Evidence),
+            max_extra_size: None,
+            attestation_keys: UnorderedMap::new(StorageKey::AttestationKeys),
+            attestations: LookupMap::new(StorageKey::Attestations),
+            require_attestation: false,
+            oracles: UnorderedSet::new(StorageKey::Oracles),
+            oracle_submissions: LookupMap::new(StorageKey::OracleSubmissions),
+            consensus_config: ConsensusConfig::default(),
+            oracle_stakes: LookupMap::new(StorageKey::OracleStakes),
+            staking_config: StakingConfig::default(),
+            slashed_balance: NearToken::from_yoctonear(0),
+            disputes: LookupMap::new(StorageKey::Disputes),
+            dispute_bond: DEFAULT_DISPUTE_BOND,
+            forfeited_bonds: NearToken::from_yoctonear(0),
+            redactions: LookupMap::new(StorageKey::Redactions),
+            opted_out_accounts: UnorderedSet::new(StorageKey::OptedOutAccounts),
+            private_cases: LookupMap::new(StorageKey::PrivateCases),
+            case_payments: LookupMap::new(StorageKey::CasePayments),
+            retry_counts: LookupMap::new(StorageKey::RetryCounts),
+            investigation_timeout_ns: U64(DEFAULT_INVESTIGATION_TIMEOUT_NS),
+            job_queue: TreeMap::new(StorageKey::JobQueue),
+            job_positions: LookupMap::new(StorageKey::JobPositions),
+            job_leases: UnorderedMap::new(StorageKey::JobLeases),
+            next_job_position: 0,
+            job_lease_duration_ns: U64(DEFAULT_JOB_LEASE_NS),
+            case_tiers: LookupMap::new(StorageKey::CaseTiers),
+            tiers: default_tiers(),
+            watchlists: LookupMap::new(StorageKey::Watchlists),
+            watchers: LookupMap::new(StorageKey::Watchers),
+            subscriptions: UnorderedMap::new(StorageKey::Subscriptions),
+            subscriptions_by_account: LookupMap::new(StorageKey::SubscriptionsByAccount),
+            next_subscription_id: 0,
+            collected_monitoring_fees: NearToken::from_yoctonear(0),
+            bounties: UnorderedMap::new(StorageKey::Bounties),
+            open_bounties: UnorderedSet::new(StorageKey::OpenBounties),
+            next_bounty_id: 0,
+            case_resolutions: LookupMap::new(StorageKey::CaseResolutions),
+            analysts: UnorderedMap::new(StorageKey::Analysts),
+            analyst_assignments: LookupMap::new(StorageKey::AnalystAssignments),
+            reputations: LookupMap::new(StorageKey::Reputations),
+            reputation_ranking: TreeMap::new(StorageKey::ReputationRanking),
+            case_ratings: LookupMap::new(StorageKey::CaseRatings),
+            rating_stats: RatingStats::default(),
+            case_notes: LookupMap::new(StorageKey::CaseNotes),
+            case_note_counts: LookupMap::new(StorageKey::CaseNoteCounts),
+            collaborators: LookupMap::new(StorageKey::Collaborators),
+            case_links: LookupMap::new(StorageKey::CaseLinks),
+            merged_cases: LookupMap::new(StorageKey::MergedCases),
+            cases_by_parent: TreeMap::new(StorageKey::CasesByParent),
+            label_rolldown: false,
+            removed_oracles: UnorderedSet::new(StorageKey::RemovedOracles),
+            subscriptions_due: TreeMap::new(StorageKey::SubscriptionsDue),
+            leases_by_expiry: TreeMap::new(StorageKey::LeasesByExpiry),
+            case_payers: LookupMap::new(StorageKey::CasePayers),
+            evidence_manifests: LookupMap::new(StorageKey::EvidenceManifests),
+            bounties_by_target: LookupMap::new(StorageKey::BountiesByTarget),
+        };
+
+        InvestigationEvent::ContractInitialized {
+            owner_id: contract.owner_id.to_string(),
+            timestamp: U64(env::block_timestamp()),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            block_height: env::block_height(),
+        }.log();
+
+        contract
+    }
+
+    pub fn set_contract_metadata(&mut self, metadata: NFTContractMetadata) {
+        self.assert_owner();
+        assert_valid_contract_metadata(&metadata);
+        self.metadata.set(&metadata);
+
+        InvestigationEvent::ContractMetadataUpdated {
+            name: metadata.name,
+            symbol: metadata.symbol,
+            timestamp: U64(env::block_timestamp()),
+        }.log();
     }
 
     #[payable]
     #[handle_result]
-    pub fn start_investigation(&mut self, target_account: AccountId) -> Result<InvestigationResponse, near_sdk::Abort> {
-        let _initial_storage_usage = env::storage_usage();
-
-    // Check for existing investigation
-    if let Some(token_id) = self.investigated_accounts.get(&target_account) {
-        return Ok(InvestigationResponse {
-            request_id: token_id.clone(),
-            status: self.investigation_status.get(&token_id).unwrap_or(InvestigationStatus::Failed),
-            message: Some("Investigation already exists".to_string()),
-        });
-    }
+    pub fn start_investigation(
+        &mut self,
+        target_account: AccountId,
+        options: Option<InvestigationOptions>,
+    ) -> Result<InvestigationResponse, near_sdk::Abort> {
+        let initial_storage_usage = env::storage_usage();
+        let options = options.unwrap_or_default();
+        options.assert_valid();
 
-    // Generate case number and token ID
-    let case_number = self.case_number_counter + 1;
-    self.case_number_counter = case_number;
-    let token_id = format!("Case File #{}: {}", case_number, target_account);
-
-    // Create initial metadata
-    let metadata = TokenMetadata {
-        title: Some(format!("Case File #{}: {}", case_number, target_account)),
-        description: Some("Investigation in progress...".to_string()),
-        media: Some(DEFAULT_NFT_IMAGE_URL.to_string()),
-        media_hash: None,
-        copies: Some(1),
-        issued_at: Some(env::block_timestamp().to_string()),
-        expires_at: None,
-        starts_at: None,
-        updated_at: Some(env::block_timestamp().to_string()),
-        extra: Some(serde_json::to_string(&InvestigationMetadata {
-            case_number,
-            target_account: target_account.clone(),
-            requester: env::predecessor_account_id(),
-            investigation_date: U64(env::block_timestamp()),
-            status: InvestigationStatus::Pending,
-            financial_summary: Some(FinancialSummary {
-                total_usd_value: "0".to_string(),
-                near_balance: "0".to_string(),
-                defi_value: "0".to_string(),
-            }),
-            analysis_summary: Some(AnalysisSummary {
-                robust_summary: None,
-                short_summary: None,
-                transaction_count: 0,
-                is_bot: false,
-            }),
-        }).unwrap()),
-        reference: None,
-        reference_hash: None,
-    };
+        require!(
+            !self.opted_out_accounts.contains(&target_account)
+                || self.is_privileged(&env::predecessor_account_id()),
+            "Target account has opted out of investigations"
+        );
 
-        // Mint NFT
-        self.tokens.internal_mint(
-            token_id.clone(),
-            target_account.clone(),
-            Some(metadata.clone())
-    );
-
-    // Update tracking
-    self.investigated_accounts.insert(&target_account, &token_id);
-    self.investigation_status.insert(&token_id, &InvestigationStatus::Pending);
-
-    // Calculate required storage deposit after all storage operations
-    // Calculate required storage deposit after all storage operations
-let required_deposit = NearToken::from_yoctonear(10_000_000_000_000_000_000_000u128); // 0.01 NEAR
-let deposit = env::attached_deposit(); // This is already NearToken
-
-require!(
-    deposit >= required_deposit,
-    "Must attach at least 0.01 NEAR for storage"
-);
-
-// If they sent more than needed, refund it
-if deposit > required_deposit {
-    let refund = deposit.saturating_sub(required_deposit); // Use saturating_sub for safe subtraction
-    Promise::new(env::predecessor_account_id())
-            .transfer(refund);
+        // Check for existing investigation
+        if let Some(token_id) = self.investigated_accounts.get(&target_account) {
+            return Ok(InvestigationResponse {
+                request_id: token_id.clone(),
+                status: self.investigation_status.get(&token_id).unwrap_or(InvestigationStatus::Failed),
+                message: Some("Investigation already exists".to_string()),
+            });
+        }
+
+        let price = self.tier_config(&options.tier).price;
+        if env::attached_deposit() < price {
+            env::log_str(&format!("Must attach at least {} for the {:?} tier", price.exact_amount_display(), options.tier));
+            return Err(near_sdk::Abort);
         }
+
+        let (token_id, case_number) = self.internal_create_case(
+            &target_account,
+            &options,
+            &env::predecessor_account_id(),
+        );
+        self.internal_record_payment(&token_id, &env::predecessor_account_id(), price);
+
+        // Calculate required deposit after all storage operations
+        let storage_cost = env::storage_byte_cost()
+            .saturating_mul(env::storage_usage().saturating_sub(initial_storage_usage) as u128);
+        let required_deposit = price
+            .saturating_add(self.analyst_fee(&options.analyst))
+            .saturating_add(storage_cost);
+        let deposit = env::attached_deposit();
+        require!(
+            deposit >= required_deposit,
+            format!("Must attach at least {} for the {:?} tier", required_deposit.exact_amount_display(), options.tier)
+        );
+        if deposit > required_deposit {
+            Promise::new(env::predecessor_account_id()).transfer(deposit.saturating_sub(required_deposit));
+        }
+
         // Log event
-    InvestigationEvent::Started {
-        target_account: target_account.to_string(),
-        token_id: token_id.clone(),
-        case_number,
-        timestamp: U64(env::block_timestamp()),
-    }.log();
-
-    Ok(InvestigationResponse {
-        request_id: token_id,
-        status: InvestigationStatus::Pending,
-        message: None,
+        InvestigationEvent::Started {
+            target_account: target_account.to_string(),
+            token_id: token_id.clone(),
+            case_number,
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+
+        Ok(InvestigationResponse {
+            request_id: token_id,
+            status: InvestigationStatus::Pending,
+            message: None,
         })
     }
 
     #[payable]
+    #[handle_result]
     pub fn update_investigation_metadata(
         &mut self,
         token_id: TokenId,
         metadata_update: MetadataUpdate,
-        webhook_type: WebhookType
+        webhook_type: WebhookType,
+        attestation: Option<SignedAttestation>,
     ) -> Result<(), near_sdk::Abort> {
-        if env::predecessor_account_id() != self.owner_id {
+        // Signed updates may be relayed by anyone; unsigned ones only by an oracle
+        // or the marketplace analyst assigned to the case
+        let analyst_update = self.is_assigned_analyst(&token_id, &env::predecessor_account_id());
+        let unsigned_allowed = analyst_update
+            || (!self.require_attestation && self.is_oracle(&env::predecessor_account_id()));
+        if attestation.is_none() && !unsigned_allowed {
             let error_msg = format!(
                 "Unauthorized update attempt from: {}", 
                 env::predecessor_account_id()
@@ -209,8 +456,25 @@ if deposit > required_deposit {
             return Err(near_sdk::Abort);
         }
 
+        // Log webhooks carry no result and never touch the stored metadata
+        if webhook_type == WebhookType::Log {
+            env::log_str("Log webhooks cannot update investigation metadata");
+            return Err(near_sdk::Abort);
+        }
+        if let Err(e) = metadata_update.validate(self.max_extra_size) {
+            env::panic_str(&e);
+        }
+        require!(
+            self.get_open_dispute(token_id.clone()).is_none(),
+            "Case is under dispute"
+        );
+        require!(
+            self.investigation_status.get(&token_id) != Some(InvestigationStatus::Merged),
+            "Case has been merged"
+        );
+
         // Detailed deserialization error logging
-        let updated_metadata: InvestigationMetadata = match serde_json::from_str(&metadata_update.extra) {
+        let mut updated_metadata: InvestigationMetadata = match serde_json::from_str(&metadata_update.extra) {
             Ok(metadata) => metadata,
             Err(e) => {
                 InvestigationEvent::DeserializationError {
@@ -218,10 +482,50 @@ if deposit > required_deposit {
                     error: e.to_string(),
                     timestamp: U64(env::block_timestamp()),
                 }.log();
-                env::panic_str(&format!("Metadata deserialization failed: {}", e));
+                return Err(near_sdk::Abort);
             }
         };
 
+        if self.consensus_config.enabled && !analyst_update && updated_metadata.status == InvestigationStatus::Completed {
+            env::panic_str("Completed results require oracle consensus, use submit_result");
+        }
+
+        if let Some(attestation) = &attestation {
+            if let Err(e) = self.verify_attestation(&token_id, &updated_metadata, attestation) {
+                env::panic_str(&e);
+            }
+        }
+
+        let stored = self.internal_investigation_metadata(&token_id)
+            .unwrap_or_else(|| env::panic_str("Investigation not found"));
+        updated_metadata.keep_case_identity(&stored);
+        require!(
+            !updated_metadata.status.is_contract_managed(),
+            format!("{:?} cannot be set through a metadata update", updated_metadata.status)
+        );
+        require!(
+            !analyst_update || matches!(
+                updated_metadata.status,
+                InvestigationStatus::Processing | InvestigationStatus::Completed
+            ),
+            "Analysts can only report Processing or Completed results"
+        );
+
+        if self.is_private_case(&token_id) {
+            assert_private_update(&updated_metadata);
+        }
+        self.apply_redactions(&token_id, &mut updated_metadata);
+        updated_metadata.tier = self.case_tiers.get(&token_id).unwrap_or_default();
+        self.apply_label_rolldown(&mut updated_metadata);
+        if self.is_private_case(&token_id) {
+            strip_sensitive_fields(&mut updated_metadata);
+        }
+        // An analyst's result stays under review until the requester accepts it
+        let analyst_submitted = analyst_update && updated_metadata.status == InvestigationStatus::Completed;
+        if analyst_submitted {
+            updated_metadata.status = InvestigationStatus::UnderReview;
+        }
+
         // Log the update attempt
         env::log_str(&format!(
             "Updating metadata for token {}: {:?}", 
@@ -229,9 +533,41 @@ if deposit > required_deposit {
             webhook_type
         ));
 
+        let previous = self.get_investigation_metadata(token_id.clone());
+
         // Update both storage locations
         self.investigation_data.insert(&token_id, &updated_metadata);
         self.investigation_status.insert(&token_id, &updated_metadata.status);
+        self.internal_sync_job(&token_id, &updated_metadata.status);
+        self.internal_refresh_artwork(&token_id, &updated_metadata);
+        if updated_metadata.status == InvestigationStatus::Completed {
+            self.internal_settle_case(&token_id);
+        }
+        // Attested results may be relayed by anyone, so only oracles are credited
+        let caller = env::predecessor_account_id();
+        let newly_completed = updated_metadata.status == InvestigationStatus::Completed
+            && previous.as_ref().is_none_or(|previous| previous.status != InvestigationStatus::Completed);
+        if analyst_submitted {
+            self.internal_mark_analyst_submitted(&token_id);
+        } else if newly_completed && self.is_oracle(&caller) {
+            self.internal_record_resolution(&token_id, vec![caller]);
+        }
+
+        let changed = previous.is_none_or(|previous| {
+            previous.status != updated_metadata.status || previous.risk_level != updated_metadata.risk_level
+        });
+        if changed {
+            self.internal_notify_watchers(&token_id, &updated_metadata);
+        }
+
+        // Keep the full report off-chain, referenced from the token
+        if metadata_update.reference.is_some() {
+            self.update_token_metadata(&token_id, |metadata| {
+                metadata.reference = metadata_update.reference.clone();
+                metadata.reference_hash = metadata_update.reference_hash.clone();
+                metadata.updated_at = Some(env::block_timestamp().to_string());
+            });
+        }
 
         // Log the update
         InvestigationEvent::MetadataUpdated {
@@ -242,9 +578,25 @@ if deposit > required_deposit {
         Ok(())
     }
 
+    /// Caps the size of `MetadataUpdate.extra`; `None` removes the cap
+    pub fn set_max_extra_size(&mut self, max_extra_size: Option<u32>) {
+        self.assert_owner();
+        self.max_extra_size = max_extra_size;
+    }
+
+    pub fn get_max_extra_size(&self) -> Option<u32> {
+        self.max_extra_size
+    }
+
     #[payable]
     #[handle_result]
     pub fn retry_investigation(&mut self, token_id: TokenId) -> Result<(), near_sdk::Abort> {
+        // Minted cases that failed or timed out are re-queued in place
+        if self.failed_mints.get(&token_id).is_none() {
+            self.internal_retry_failed_case(token_id);
+            return Ok(());
+        }
+
         let failed_metadata_str = self.failed_mints.get(&token_id)
             .ok_or_else(|| env::panic_str("No failed mint found for this token"))?;
         
@@ -296,7 +648,8 @@ if deposit > required_deposit {
     }
 
     pub fn nft_metadata(&self) -> NFTContractMetadata {
-        self.metadata.get().unwrap()
+        // Deployments initialised before metadata was configurable have none stored
+        self.metadata.get().unwrap_or_else(default_contract_metadata)
     }
 
     #[private]
@@ -305,10 +658,7 @@ if deposit > required_deposit {
         // Enhanced logging for migration
         env::log_str("Starting contract migration...");
         
-        let old_state: Contract = env::state_read().map_err(|e| {
-            env::log_str(&format!("Failed to read old state: {:?}", e));
-            env::panic_str("State read failed during migration")
-        }).expect("Failed to read state");
+        let old_state: OldState = env::state_read().expect("Failed to read state");
         
         env::log_str(&format!(
             "Old state loaded - Version: {}, Cases: {}", 
@@ -321,16 +671,87 @@ if deposit > required_deposit {
             "Can only migrate from version 1"
         );
 
+        // Version 1 case records lack the newer metadata fields, so each one is read in the
+        // old layout, the map is cleared and the records are written back re-encoded
+        let mut old_investigation_data = old_state.investigation_data;
+        let old_records: Vec<(TokenId, OldInvestigationMetadata)> = old_investigation_data.iter().collect();
+        old_investigation_data.clear();
+        let mut investigation_data = UnorderedMap::new(StorageKey::InvestigationData);
+        for (token_id, old_metadata) in old_records {
+            investigation_data.insert(&token_id, &InvestigationMetadata::from(old_metadata));
+        }
+
+        // Everything added since version 1 starts out as in `new`
         let new_contract = Self {
             version: old_state.version + 1,
             tokens: old_state.tokens,
             owner_id: old_state.owner_id.clone(),
             metadata: old_state.metadata,
             case_number_counter: old_state.case_number_counter,
-            investigated_accounts: LookupMap::new(StorageKey::InvestigatedAccounts),
-            investigation_status: UnorderedMap::new(StorageKey::InvestigationStatus),
-            investigation_data: UnorderedMap::new(StorageKey::InvestigationData),
-            failed_mints: UnorderedMap::new(StorageKey::FailedMints),
+            investigated_accounts: old_state.investigated_accounts,
+            investigation_status: old_state.investigation_status,
+            investigation_data,
+            failed_mints: old_state.failed_mints,
+            edges: Vector::new(StorageKey::Edges),
+            edges_from: LookupMap::new(StorageKey::EdgesFrom),
+            edges_to: LookupMap::new(StorageKey::EdgesTo),
+            moderators: UnorderedSet::new(StorageKey::Moderators),
+            evidence: LookupMap::new(StorageKey::Evidence),
+            max_extra_size: None,
+            attestation_keys: UnorderedMap::new(StorageKey::AttestationKeys),
+            attestations: LookupMap::new(StorageKey::Attestations),
+            require_attestation: false,
+            oracles: UnorderedSet::new(StorageKey::Oracles),
+            oracle_submissions: LookupMap::new(StorageKey::OracleSubmissions),
+            consensus_config: ConsensusConfig::default(),
+            oracle_stakes: LookupMap::new(StorageKey::OracleStakes),
+            staking_config: StakingConfig::default(),
+            slashed_balance: NearToken::from_yoctonear(0),
+            disputes: LookupMap::new(StorageKey::Disputes),
+            dispute_bond: DEFAULT_DISPUTE_BOND,
+            forfeited_bonds: NearToken::from_yoctonear(0),
+            redactions: LookupMap::new(StorageKey::Redactions),
+            opted_out_accounts: UnorderedSet::new(StorageKey::OptedOutAccounts),
+            private_cases: LookupMap::new(StorageKey::PrivateCases),
+            case_payments: LookupMap::new(StorageKey::CasePayments),
+            retry_counts: LookupMap::new(StorageKey::RetryCounts),
+            investigation_timeout_ns: U64(DEFAULT_INVESTIGATION_TIMEOUT_NS),
+            job_queue: TreeMap::new(StorageKey::JobQueue),
+            job_positions: LookupMap::new(StorageKey::JobPositions),
+            job_leases: UnorderedMap::new(StorageKey::JobLeases),
+            next_job_position: 0,
+            job_lease_duration_ns: U64(DEFAULT_JOB_LEASE_NS),
+            case_tiers: LookupMap::new(StorageKey::CaseTiers),
+            tiers: default_tiers(),
+            watchlists: LookupMap::new(StorageKey::Watchlists),
+            watchers: LookupMap::new(StorageKey::Watchers),
+            subscriptions: UnorderedMap::new(StorageKey::Subscriptions),
+            subscriptions_by_account: LookupMap::new(StorageKey::SubscriptionsByAccount),
+            next_subscription_id: 0,
+            collected_monitoring_fees: NearToken::from_yoctonear(0),
+            bounties: UnorderedMap::new(StorageKey::Bounties),
+            open_bounties: UnorderedSet::new(StorageKey::OpenBounties),
+            next_bounty_id: 0,
+            case_resolutions: LookupMap::new(StorageKey::CaseResolutions),
+            analysts: UnorderedMap::new(StorageKey::Analysts),
+            analyst_assignments: LookupMap::new(StorageKey::AnalystAssignments),
+            reputations: LookupMap::new(StorageKey::Reputations),
+            reputation_ranking: TreeMap::new(StorageKey::ReputationRanking),
+            case_ratings: LookupMap::new(StorageKey::CaseRatings),
+            rating_stats: RatingStats::default(),
+            case_notes: LookupMap::new(StorageKey::CaseNotes),
+            case_note_counts: LookupMap::new(StorageKey::CaseNoteCounts),
+            collaborators: LookupMap::new(StorageKey::Collaborators),
+            case_links: LookupMap::new(StorageKey::CaseLinks),
+            merged_cases: LookupMap::new(StorageKey::MergedCases),
+            cases_by_parent: TreeMap::new(StorageKey::CasesByParent),
+            label_rolldown: false,
+            removed_oracles: UnorderedSet::new(StorageKey::RemovedOracles),
+            subscriptions_due: TreeMap::new(StorageKey::SubscriptionsDue),
+            leases_by_expiry: TreeMap::new(StorageKey::LeasesByExpiry),
+            case_payers: LookupMap::new(StorageKey::CasePayers),
+            evidence_manifests: LookupMap::new(StorageKey::EvidenceManifests),
+            bounties_by_target: LookupMap::new(StorageKey::BountiesByTarget),
         };
 
         // Log successful migration
@@ -343,7 +764,15 @@ if deposit > required_deposit {
         new_contract
     }
 
+    fn assert_oracle(&self) {
+        require!(
+            self.is_oracle(&env::predecessor_account_id()),
+            "Only the oracle can call this method"
+        );
+    }
+
     fn create_token_metadata(&self, investigation: &InvestigationMetadata) -> TokenMetadata {
+        let (media, media_hash) = case_artwork(investigation, self.hides_artwork_target(investigation));
         TokenMetadata {
             title: Some(format!("Case File #{}: {}", 
                 investigation.case_number, 
@@ -351,21 +780,125 @@ if deposit > required_deposit {
             )),
             description: Some(investigation.summary.clone()
                 .unwrap_or_else(|| "Investigation in progress...".to_string())),
-            media: Some(DEFAULT_NFT_IMAGE_URL.to_string()),
+            media: Some(media),
             copies: Some(1),
             issued_at: Some(env::block_timestamp().to_string()),
             updated_at: Some(env::block_timestamp().to_string()),
             extra: Some(serde_json::to_string(&investigation).unwrap_or_default()),
+            media_hash: Some(media_hash),
             // Remove unused fields
-            media_hash: None,
             expires_at: None,
             starts_at: None,
+            // Set by the full report, not the evidence manifest (see `EvidenceManifestRecord`)
             reference: None,
             reference_hash: None,
         }
     }
 }
 
+impl Contract {
+    /// Mints the case file NFT for `target_account` and registers it as a pending job.
+    /// Deposit and storage handling are left to the caller; the mint itself never refunds.
+    pub(crate) fn internal_create_case(
+        &mut self,
+        target_account: &AccountId,
+        options: &InvestigationOptions,
+        requester: &AccountId,
+    ) -> (TokenId, u64) {
+        let case_number = self.case_number_counter + 1;
+        self.case_number_counter = case_number;
+        let token_id = format!("Case File #{}: {}", case_number, target_account);
+        let inherited_risk = if self.label_rolldown { self.inherited_risk(target_account) } else { None };
+
+        let investigation = InvestigationMetadata {
+            case_number,
+            target_account: target_account.clone(),
+            requester: requester.clone(),
+            investigation_date: U64(env::block_timestamp()),
+            status: InvestigationStatus::Pending,
+            visibility: options.visibility.clone(),
+            tier: options.tier.clone(),
+            account_kind: AccountKind::of(target_account),
+            parent_accounts: parent_accounts(target_account),
+            risk_level: inherited_risk.as_ref().map(|(risk_level, _)| risk_level.clone()).unwrap_or_default(),
+            risk_inherited_from: inherited_risk.map(|(_, parent)| parent),
+            ..InvestigationMetadata::new(case_number, target_account.clone(), requester.clone())
+        };
+        let metadata = TokenMetadata {
+            description: Some(match options.visibility {
+                Visibility::Private => PRIVATE_CASE_DESCRIPTION.to_string(),
+                Visibility::Public => "Investigation in progress...".to_string(),
+            }),
+            expires_at: options.retention_period_ns
+                .map(|retention| env::block_timestamp().saturating_add(retention.0).to_string()),
+            ..self.create_token_metadata(&investigation)
+        };
+
+        self.tokens.internal_mint_with_refund(
+            token_id.clone(),
+            target_account.clone(),
+            Some(metadata),
+            None,
+        );
+
+        self.investigated_accounts.insert(target_account, &token_id);
+        self.internal_index_by_parent(&token_id, target_account);
+        self.investigation_status.insert(&token_id, &InvestigationStatus::Pending);
+        self.case_tiers.insert(&token_id, &options.tier);
+        if let Some(analyst_id) = &options.analyst {
+            self.internal_assign_analyst(&token_id, analyst_id, requester);
+        }
+        self.internal_sync_job(&token_id, &InvestigationStatus::Pending);
+        if let (Visibility::Private, Some(public_key)) = (&options.visibility, &options.encryption_public_key) {
+            self.private_cases.insert(&token_id, public_key);
+        }
+
+        (token_id, case_number)
+    }
+
+    /// Moves a case to `status`, keeping `investigation_data` in sync
+    pub(crate) fn internal_set_status(&mut self, token_id: &TokenId, status: InvestigationStatus) {
+        let old_status = self.investigation_status.get(token_id);
+        self.investigation_status.insert(token_id, &status);
+        self.internal_sync_job(token_id, &status);
+        if let Some(mut data) = self.investigation_data.get(token_id) {
+            data.status = status.clone();
+            data.last_updated = U64(env::block_timestamp());
+            self.investigation_data.insert(token_id, &data);
+        }
+        if old_status.as_ref() != Some(&status) {
+            if let Some(mut metadata) = self.get_investigation_metadata(token_id.clone()) {
+                metadata.status = status.clone();
+                metadata.last_updated = U64(env::block_timestamp());
+                // Keeps `last_updated` fresh for cases that have only ever had token metadata
+                self.investigation_data.insert(token_id, &metadata);
+                self.internal_notify_watchers(token_id, &metadata);
+                self.internal_refresh_artwork(token_id, &metadata);
+            }
+        }
+        if status == InvestigationStatus::Completed {
+            self.internal_settle_case(token_id);
+        }
+
+        InvestigationEvent::StatusChanged {
+            token_id: token_id.clone(),
+            old_status: old_status.map(|status| format!("{:?}", status)).unwrap_or_default(),
+            new_status: format!("{:?}", status),
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+    }
+
+    /// Applies `update` to the stored NEP-177 metadata of `token_id`, if any
+    pub(crate) fn update_token_metadata<F: FnOnce(&mut TokenMetadata)>(&mut self, token_id: &TokenId, update: F) {
+        if let Some(token_metadata_by_id) = self.tokens.token_metadata_by_id.as_mut() {
+            if let Some(mut metadata) = token_metadata_by_id.get(token_id) {
+                update(&mut metadata);
+                token_metadata_by_id.insert(token_id, &metadata);
+            }
+        }
+    }
+}
+
 #[near_bindgen]
 impl NonFungibleTokenCore for Contract {
     #[payable]
@@ -396,34 +929,5 @@ impl NonFungibleTokenCore for Contract {
     }
 }
 
-// Keep your test module at the bottom
 #[cfg(test)]
-#[cfg(feature = "test-utils")]
-mod tests {
-    use super::*;
-    use near_sdk::test_utils::accounts;
-    use near_sdk::test_utils::VMContextBuilder;
-    use near_sdk::testing_env;
-
-    fn get_context() -> VMContextBuilder {
-        let mut builder = VMContextBuilder::new();
-        builder
-            .current_account_id(accounts(0))
-            .signer_account_id(accounts(0))
-            .predecessor_account_id(accounts(0));
-        builder
-    }
-
-    #[test]
-    fn test_new() {
-        let context = get_context();
-        testing_env!(context.build());
-
-        let contract = Contract::new(accounts(0));
-        assert_eq!(contract.owner_id, accounts(0));
-        assert_eq!(contract.case_number_counter, 0);
-    }
-
-    // Additional tests can be added here
-}
-
+mod test;
@@ -0,0 +1,145 @@
+use std::prelude::v1::*;
+use crate::*;
+use crate::evidence::MAX_EVIDENCE_PER_CASE;
+use crate::notes::MAX_NOTES_PER_CASE;
+use near_sdk::serde::{Deserialize, Serialize};
+
+pub const MAX_LINKS_PER_CASE: usize = 50;
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum CaseRelation {
+    Related,
+    Duplicate,
+    SameController,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CaseLink {
+    pub token_id: TokenId,
+    pub relation: CaseRelation,
+    pub linked_by: AccountId,
+    pub linked_at: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Records a symmetric relationship between two cases
+    pub fn link_cases(&mut self, token_a: TokenId, token_b: TokenId, relation: CaseRelation) {
+        let caller = env::predecessor_account_id();
+        require!(
+            self.is_oracle(&caller)
+                || self.moderators.contains(&caller)
+                || (self.is_collaborator(&token_a, &caller) && self.is_collaborator(&token_b, &caller)),
+            "Only the oracle, a moderator or a collaborator on both cases can link them"
+        );
+        self.internal_link_cases(&token_a, &token_b, relation, &caller);
+    }
+
+    /// Folds `duplicate` into `primary`: the duplicate's target (and anything previously
+    /// merged into it) resolves to `primary`, its evidence and notes are appended to
+    /// `primary`, and the duplicate is marked `Merged`. Unless the duplicate had been
+    /// completed, its fee, analyst escrow and open bounties are refunded.
+    pub fn merge_cases(&mut self, primary: TokenId, duplicate: TokenId) {
+        self.assert_moderator();
+        require!(primary != duplicate, "Cannot merge a case into itself");
+        let duplicate_case = self.get_investigation_metadata(duplicate.clone())
+            .unwrap_or_else(|| env::panic_str("Duplicate investigation not found"));
+        require!(self.investigation_status.get(&primary).is_some(), "Primary investigation not found");
+        for token_id in [&primary, &duplicate] {
+            require!(
+                self.investigation_status.get(token_id) != Some(InvestigationStatus::Merged),
+                "Case has already been merged"
+            );
+            require!(self.get_open_dispute(token_id.clone()).is_none(), "Case is under dispute");
+        }
+
+        let mut evidence = self.evidence.get(&primary).unwrap_or_default();
+        evidence.extend(self.evidence.remove(&duplicate).unwrap_or_default());
+        require!(evidence.len() <= MAX_EVIDENCE_PER_CASE, "Merged evidence exceeds the per-case limit");
+        if !evidence.is_empty() {
+            self.evidence.insert(&primary, &evidence);
+            self.internal_refresh_evidence_manifest(&primary, None);
+        }
+        self.evidence_manifests.remove(&duplicate);
+        let primary_notes = self.case_note_counts.get(&primary).unwrap_or(0) as usize;
+        let duplicate_notes = self.case_note_counts.get(&duplicate).unwrap_or(0) as usize;
+        require!(primary_notes + duplicate_notes <= MAX_NOTES_PER_CASE, "Merged notes exceed the per-case limit");
+        for note in self.internal_remove_case_notes(&duplicate) {
+            self.internal_push_case_note(&primary, &note);
+        }
+
+        let mut absorbed = self.merged_cases.remove(&duplicate).unwrap_or_default();
+        absorbed.push(duplicate.clone());
+        for token_id in absorbed.iter() {
+            if let Some(investigation) = self.get_investigation_metadata(token_id.clone()) {
+                self.investigated_accounts.insert(&investigation.target_account, &primary);
+            }
+        }
+        let mut merged = self.merged_cases.get(&primary).unwrap_or_default();
+        merged.extend(absorbed);
+        self.merged_cases.insert(&primary, &merged);
+
+        // Nothing was delivered on an unfinished duplicate, so everything paid for it goes back
+        if self.investigation_status.get(&duplicate) != Some(InvestigationStatus::Completed) {
+            self.internal_refund_case(&duplicate);
+            self.internal_refund_target_bounties(&duplicate_case.target_account);
+        }
+        self.internal_link_cases(&primary, &duplicate, CaseRelation::Duplicate, &env::predecessor_account_id());
+        self.internal_set_status(&duplicate, InvestigationStatus::Merged);
+        self.update_token_metadata(&duplicate, |metadata| {
+            metadata.description = Some(format!("Merged into {}", primary));
+            metadata.updated_at = Some(env::block_timestamp().to_string());
+        });
+
+        InvestigationEvent::CasesMerged {
+            primary,
+            duplicate,
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+    }
+
+    pub fn get_case_links(&self, token_id: TokenId) -> Vec<CaseLink> {
+        self.case_links.get(&token_id).unwrap_or_default()
+    }
+
+    /// Cases that have been merged into `token_id`
+    pub fn get_merged_cases(&self, token_id: TokenId) -> Vec<TokenId> {
+        self.merged_cases.get(&token_id).unwrap_or_default()
+    }
+}
+
+impl Contract {
+    fn internal_link_cases(&mut self, token_a: &TokenId, token_b: &TokenId, relation: CaseRelation, linked_by: &AccountId) {
+        require!(token_a != token_b, "Cannot link a case to itself");
+        require!(
+            self.investigation_status.get(token_a).is_some() && self.investigation_status.get(token_b).is_some(),
+            "Investigation not found"
+        );
+        for (from, to) in [(token_a, token_b), (token_b, token_a)] {
+            let mut links = self.case_links.get(from).unwrap_or_default();
+            if links.iter().any(|link| &link.token_id == to && link.relation == relation) {
+                continue;
+            }
+            require!(
+                links.len() < MAX_LINKS_PER_CASE,
+                format!("Cannot link more than {} cases", MAX_LINKS_PER_CASE)
+            );
+            links.push(CaseLink {
+                token_id: to.clone(),
+                relation: relation.clone(),
+                linked_by: linked_by.clone(),
+                linked_at: U64(env::block_timestamp()),
+            });
+            self.case_links.insert(from, &links);
+        }
+
+        InvestigationEvent::CasesLinked {
+            token_a: token_a.clone(),
+            token_b: token_b.clone(),
+            relation: format!("{:?}", relation),
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+    }
+}
@@ -1,18 +1,94 @@
 use near_contract_standards::non_fungible_token::metadata::{
+    NFTContractMetadata,
     TokenMetadata,
+    NFT_METADATA_SPEC,
 };
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::env;
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::{env, require};
+use crate::{DEFAULT_BASE_URI, DEFAULT_CONTRACT_NAME, DEFAULT_CONTRACT_SYMBOL, DEFAULT_ICON_URL};
+
+pub const MAX_REFERENCE_LENGTH: usize = 512;
+pub const MAX_CONTRACT_NAME_LENGTH: usize = 64;
+pub const MAX_CONTRACT_SYMBOL_LENGTH: usize = 16;
+
+pub fn default_contract_metadata() -> NFTContractMetadata {
+    NFTContractMetadata {
+        spec: NFT_METADATA_SPEC.to_string(),
+        name: DEFAULT_CONTRACT_NAME.to_string(),
+        symbol: DEFAULT_CONTRACT_SYMBOL.to_string(),
+        icon: Some(DEFAULT_ICON_URL.to_string()),
+        base_uri: Some(DEFAULT_BASE_URI.to_string()),
+        reference: None,
+        reference_hash: None,
+    }
+}
+
+/// NEP-177 checks plus bounds on the fields wallets display
+pub fn assert_valid_contract_metadata(metadata: &NFTContractMetadata) {
+    metadata.assert_valid();
+    require!(
+        !metadata.name.is_empty() && metadata.name.len() <= MAX_CONTRACT_NAME_LENGTH,
+        "Invalid contract name"
+    );
+    require!(
+        !metadata.symbol.is_empty()
+            && metadata.symbol.len() <= MAX_CONTRACT_SYMBOL_LENGTH
+            && !metadata.symbol.contains(char::is_whitespace),
+        "Invalid contract symbol"
+    );
+    if let Some(icon) = &metadata.icon {
+        require!(
+            icon.starts_with("data:") || icon.starts_with("https://") || icon.starts_with("ipfs://"),
+            "Icon must be a data URL or an https/ipfs URL"
+        );
+    }
+    for uri in [&metadata.base_uri, &metadata.reference].into_iter().flatten() {
+        require!(!uri.is_empty() && uri.len() <= MAX_REFERENCE_LENGTH, "Invalid metadata URI");
+    }
+}
 
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct MetadataUpdate {
     pub description: Option<String>,
     pub extra: String,
+    /// URI of the full off-chain report JSON (NEP-177 `reference`)
+    #[serde(default)]
+    pub reference: Option<String>,
+    /// Base64-encoded sha256 of the JSON behind `reference`
+    #[serde(default)]
+    pub reference_hash: Option<Base64VecU8>,
 }
 
 impl MetadataUpdate {
+    pub fn validate(&self, max_extra_size: Option<u32>) -> Result<(), String> {
+        if let Some(max_extra_size) = max_extra_size {
+            if self.extra.len() > max_extra_size as usize {
+                return Err(format!(
+                    "Metadata extra is {} bytes, exceeds the cap of {} bytes",
+                    self.extra.len(),
+                    max_extra_size
+                ));
+            }
+        }
+        if self.reference.is_some() != self.reference_hash.is_some() {
+            return Err("reference and reference_hash must be provided together".to_string());
+        }
+        if let Some(reference) = &self.reference {
+            if reference.is_empty() || reference.len() > MAX_REFERENCE_LENGTH {
+                return Err("Invalid reference URI".to_string());
+            }
+        }
+        if let Some(reference_hash) = &self.reference_hash {
+            if reference_hash.0.len() != 32 {
+                return Err("reference_hash must be a base64-encoded 32 byte sha256".to_string());
+            }
+        }
+        Ok(())
+    }
+
     pub fn to_token_metadata(&self) -> Result<TokenMetadata, String> {
         Ok(TokenMetadata {
             title: None,
@@ -25,8 +101,8 @@ impl MetadataUpdate {
             starts_at: None,
             updated_at: Some(env::block_timestamp().to_string()),
             extra: Some(self.extra.clone()),
-            reference: None,
-            reference_hash: None,
+            reference: self.reference.clone(),
+            reference_hash: self.reference_hash.clone(),
         })
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,266 @@
+use std::prelude::v1::*;
+use crate::*;
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// 1 hour
+pub const MIN_MONITORING_INTERVAL_NS: u64 = 60 * 60 * 1_000_000_000;
+pub const MAX_MONITORING_PERIODS: u32 = 365;
+pub const MAX_SUBSCRIPTIONS_PER_ACCOUNT: usize = 20;
+/// Upper bound on subscriptions processed by one `tick_monitoring` call
+const MAX_TICK_BATCH: u32 = 20;
+/// Storage prepaid for each run, enough for the case file a first run mints
+pub const MONITORING_RUN_STORAGE_BYTES: u64 = 4_000;
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MonitoringSubscription {
+    pub id: u64,
+    pub subscriber: AccountId,
+    pub target_account: AccountId,
+    pub interval_ns: U64,
+    pub periods_remaining: u32,
+    pub fee_per_period: NearToken,
+    /// Part of `fee_per_period` set aside for the storage a run adds
+    pub storage_per_period: NearToken,
+    pub next_run_at: U64,
+    pub created_at: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Prepays `periods` re-investigations of `target_account`, one every `interval_ns`
+    #[payable]
+    pub fn subscribe_monitoring(&mut self, target_account: AccountId, interval_ns: U64, periods: u32) -> MonitoringSubscription {
+        let subscriber = env::predecessor_account_id();
+        require!(interval_ns.0 >= MIN_MONITORING_INTERVAL_NS, "Monitoring interval is too short");
+        require!(
+            (1..=MAX_MONITORING_PERIODS).contains(&periods),
+            format!("Periods must be between 1 and {}", MAX_MONITORING_PERIODS)
+        );
+        require!(
+            !self.opted_out_accounts.contains(&target_account) || self.is_privileged(&subscriber),
+            "Target account has opted out of investigations"
+        );
+        let mut subscriber_ids = self.subscriptions_by_account.get(&subscriber).unwrap_or_default();
+        require!(
+            subscriber_ids.len() < MAX_SUBSCRIPTIONS_PER_ACCOUNT,
+            "Too many active monitoring subscriptions"
+        );
+
+        let fee_per_period = self.get_monitoring_fee();
+        let required_deposit = fee_per_period.saturating_mul(periods as u128);
+        let deposit = env::attached_deposit();
+        require!(
+            deposit >= required_deposit,
+            format!("Must attach at least {} for {} periods", required_deposit.exact_amount_display(), periods)
+        );
+        if deposit > required_deposit {
+            Promise::new(subscriber.clone()).transfer(deposit.saturating_sub(required_deposit));
+        }
+
+        let subscription = MonitoringSubscription {
+            id: self.next_subscription_id,
+            subscriber: subscriber.clone(),
+            target_account,
+            interval_ns,
+            periods_remaining: periods,
+            fee_per_period,
+            storage_per_period: self.monitoring_storage_cost(),
+            next_run_at: U64(env::block_timestamp() + interval_ns.0),
+            created_at: U64(env::block_timestamp()),
+        };
+        self.next_subscription_id += 1;
+        self.internal_save_subscription(&subscription);
+        subscriber_ids.push(subscription.id);
+        self.subscriptions_by_account.insert(&subscriber, &subscriber_ids);
+
+        InvestigationEvent::MonitoringSubscribed {
+            subscription_id: U64(subscription.id),
+            subscriber: subscriber.to_string(),
+            target_account: subscription.target_account.to_string(),
+            periods,
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+
+        subscription
+    }
+
+    /// Fee charged per monitoring period: a Standard investigation plus its storage
+    pub fn get_monitoring_fee(&self) -> NearToken {
+        self.tier_config(&InvestigationTier::Standard).price
+            .saturating_add(self.monitoring_storage_cost())
+    }
+
+    /// Keeper entry point: queues a re-investigation for every due subscription,
+    /// charging one period each. Returns the ids of the subscriptions that ran.
+    pub fn tick_monitoring(&mut self, limit: Option<u32>) -> Vec<U64> {
+        let limit = limit.unwrap_or(MAX_TICK_BATCH).min(MAX_TICK_BATCH) as usize;
+        let now = env::block_timestamp();
+        let due: Vec<MonitoringSubscription> = self.subscriptions_due
+            .iter()
+            .take_while(|((next_run_at, _), _)| *next_run_at <= now)
+            .take(limit)
+            .filter_map(|((_, id), _)| self.subscriptions.get(&id))
+            .collect();
+
+        let mut triggered = Vec::new();
+        for mut subscription in due {
+            // Targets can opt out after the subscription was paid for
+            if self.opted_out_accounts.contains(&subscription.target_account)
+                && !self.is_privileged(&subscription.subscriber)
+            {
+                self.internal_cancel_subscription(&subscription, false);
+                continue;
+            }
+
+            self.subscriptions_due.remove(&(subscription.next_run_at.0, subscription.id));
+            subscription.next_run_at = U64(now + subscription.interval_ns.0);
+            let initial_storage_usage = env::storage_usage();
+            let token_id = match self.investigated_accounts.get(&subscription.target_account) {
+                Some(token_id) => {
+                    let status = self.investigation_status.get(&token_id);
+                    // A run already in flight or under review covers this period for free
+                    if status != Some(InvestigationStatus::Completed) && !status.as_ref().is_some_and(InvestigationStatus::is_retryable) {
+                        self.internal_save_subscription(&subscription);
+                        continue;
+                    }
+                    self.internal_set_status(&token_id, InvestigationStatus::Pending);
+                    token_id
+                }
+                None => {
+                    let (token_id, _) = self.internal_create_case(
+                        &subscription.target_account,
+                        &InvestigationOptions::default(),
+                        &subscription.subscriber,
+                    );
+                    token_id
+                }
+            };
+
+            // The run is escrowed like a direct request; storage it did not use is kept as a fee
+            self.internal_record_payment(
+                &token_id,
+                &subscription.subscriber,
+                subscription.fee_per_period.saturating_sub(subscription.storage_per_period),
+            );
+            let storage_cost = env::storage_byte_cost()
+                .saturating_mul(env::storage_usage().saturating_sub(initial_storage_usage) as u128);
+            subscription.periods_remaining -= 1;
+            self.collected_monitoring_fees = self.collected_monitoring_fees
+                .saturating_add(subscription.storage_per_period.saturating_sub(storage_cost));
+            if subscription.periods_remaining == 0 {
+                self.internal_remove_subscription(&subscription);
+            } else {
+                self.internal_save_subscription(&subscription);
+            }
+
+            InvestigationEvent::MonitoringTriggered {
+                subscription_id: U64(subscription.id),
+                token_id,
+                periods_remaining: subscription.periods_remaining,
+                timestamp: U64(now),
+            }.log();
+            triggered.push(U64(subscription.id));
+        }
+        triggered
+    }
+
+    /// Cancels a subscription and refunds the unused periods, less the elapsed share
+    /// of the current one
+    pub fn cancel_monitoring(&mut self, subscription_id: U64) -> NearToken {
+        let subscription = self.subscriptions.get(&subscription_id.0)
+            .unwrap_or_else(|| env::panic_str("Subscription not found"));
+        require!(
+            env::predecessor_account_id() == subscription.subscriber,
+            "Only the subscriber can cancel monitoring"
+        );
+        self.internal_cancel_subscription(&subscription, true)
+    }
+
+    /// Sends kept monitoring fees to the owner; withdraws everything when `amount` is omitted
+    pub fn withdraw_monitoring_fees(&mut self, amount: Option<NearToken>) -> Promise {
+        self.assert_owner();
+        let amount = amount.unwrap_or(self.collected_monitoring_fees);
+        require!(!amount.is_zero() && amount <= self.collected_monitoring_fees, "Invalid withdrawal amount");
+        self.collected_monitoring_fees = self.collected_monitoring_fees.saturating_sub(amount);
+
+        InvestigationEvent::MonitoringFeesWithdrawn {
+            receiver_id: self.owner_id.to_string(),
+            amount,
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+
+        Promise::new(self.owner_id.clone()).transfer(amount)
+    }
+
+    pub fn get_collected_monitoring_fees(&self) -> NearToken {
+        self.collected_monitoring_fees
+    }
+
+    pub fn get_monitoring_subscription(&self, subscription_id: U64) -> Option<MonitoringSubscription> {
+        self.subscriptions.get(&subscription_id.0)
+    }
+
+    pub fn get_monitoring_subscriptions_for(&self, account_id: AccountId) -> Vec<MonitoringSubscription> {
+        self.subscriptions_by_account.get(&account_id)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|id| self.subscriptions.get(id))
+            .collect()
+    }
+}
+
+impl Contract {
+    fn monitoring_storage_cost(&self) -> NearToken {
+        env::storage_byte_cost().saturating_mul(MONITORING_RUN_STORAGE_BYTES as u128)
+    }
+
+    fn internal_save_subscription(&mut self, subscription: &MonitoringSubscription) {
+        self.subscriptions.insert(&subscription.id, subscription);
+        self.subscriptions_due.insert(&(subscription.next_run_at.0, subscription.id), &());
+    }
+
+    fn internal_remove_subscription(&mut self, subscription: &MonitoringSubscription) {
+        self.subscriptions.remove(&subscription.id);
+        self.subscriptions_due.remove(&(subscription.next_run_at.0, subscription.id));
+        let mut subscriber_ids = self.subscriptions_by_account.get(&subscription.subscriber).unwrap_or_default();
+        subscriber_ids.retain(|id| *id != subscription.id);
+        if subscriber_ids.is_empty() {
+            self.subscriptions_by_account.remove(&subscription.subscriber);
+        } else {
+            self.subscriptions_by_account.insert(&subscription.subscriber, &subscriber_ids);
+        }
+    }
+
+    /// Ends a subscription and refunds the unused periods. With `prorate`, the share
+    /// of the current period that has already elapsed is kept as a fee.
+    fn internal_cancel_subscription(&mut self, subscription: &MonitoringSubscription, prorate: bool) -> NearToken {
+        let interval_ns = subscription.interval_ns.0;
+        let period_start = subscription.next_run_at.0.saturating_sub(interval_ns);
+        let elapsed_ns = if prorate {
+            env::block_timestamp().saturating_sub(period_start).min(interval_ns)
+        } else {
+            0
+        };
+        let consumed = NearToken::from_yoctonear(
+            subscription.fee_per_period.as_yoctonear().saturating_mul(elapsed_ns as u128) / interval_ns as u128,
+        );
+        let refund = subscription.fee_per_period
+            .saturating_mul(subscription.periods_remaining as u128)
+            .saturating_sub(consumed);
+        self.collected_monitoring_fees = self.collected_monitoring_fees.saturating_add(consumed);
+
+        self.internal_remove_subscription(subscription);
+        if !refund.is_zero() {
+            Promise::new(subscription.subscriber.clone()).transfer(refund);
+        }
+
+        InvestigationEvent::MonitoringCancelled {
+            subscription_id: U64(subscription.id),
+            refund,
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+
+        refund
+    }
+}
@@ -0,0 +1,158 @@
+use std::prelude::v1::*;
+use crate::*;
+use near_sdk::serde::{Deserialize, Serialize};
+
+pub const MAX_NOTES_PER_CASE: usize = 200;
+pub const MAX_NOTE_LENGTH: usize = 2_000;
+pub const MAX_COLLABORATORS_PER_CASE: usize = 20;
+const MAX_NOTES_LIMIT: u64 = 50;
+
+/// Notes live in contract state and views cannot authenticate the caller, so every
+/// note is public; collaborators who need privacy should encrypt the body client-side
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum NoteVisibility {
+    Public,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CaseNote {
+    pub author: AccountId,
+    pub timestamp: U64,
+    pub body: String,
+    pub visibility: NoteVisibility,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn add_collaborator(&mut self, token_id: TokenId, account_id: AccountId) {
+        let requester = self.assert_case_requester(&token_id);
+        require!(account_id != requester, "The requester is already a collaborator");
+        let mut collaborators = self.collaborators.get(&token_id).unwrap_or_default();
+        require!(!collaborators.contains(&account_id), "Account is already a collaborator");
+        require!(
+            collaborators.len() < MAX_COLLABORATORS_PER_CASE,
+            format!("Cannot add more than {} collaborators", MAX_COLLABORATORS_PER_CASE)
+        );
+        collaborators.push(account_id.clone());
+        self.collaborators.insert(&token_id, &collaborators);
+
+        InvestigationEvent::CollaboratorAdded {
+            token_id,
+            account_id: account_id.to_string(),
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+    }
+
+    pub fn remove_collaborator(&mut self, token_id: TokenId, account_id: AccountId) {
+        self.assert_case_requester(&token_id);
+        let mut collaborators = self.collaborators.get(&token_id).unwrap_or_default();
+        let position = collaborators.iter().position(|collaborator| collaborator == &account_id)
+            .unwrap_or_else(|| env::panic_str("Account is not a collaborator"));
+        collaborators.remove(position);
+        if collaborators.is_empty() {
+            self.collaborators.remove(&token_id);
+        } else {
+            self.collaborators.insert(&token_id, &collaborators);
+        }
+
+        InvestigationEvent::CollaboratorRemoved {
+            token_id,
+            account_id: account_id.to_string(),
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+    }
+
+    /// Appends a note to the case log; the author pays for the storage used
+    #[payable]
+    pub fn add_case_note(&mut self, token_id: TokenId, body: String) -> u32 {
+        let author = env::predecessor_account_id();
+        require!(self.is_collaborator(&token_id, &author), "Only the requester or a collaborator can add notes");
+        require!(!body.is_empty() && body.len() <= MAX_NOTE_LENGTH, "Invalid note body");
+        let initial_storage_usage = env::storage_usage();
+
+        let index = self.case_note_counts.get(&token_id).unwrap_or(0);
+        require!(
+            (index as usize) < MAX_NOTES_PER_CASE,
+            format!("Cannot add more than {} notes to a case", MAX_NOTES_PER_CASE)
+        );
+        self.internal_push_case_note(&token_id, &CaseNote {
+            author: author.clone(),
+            timestamp: U64(env::block_timestamp()),
+            body,
+            visibility: NoteVisibility::Public,
+        });
+
+        let storage_cost = env::storage_byte_cost()
+            .saturating_mul(env::storage_usage().saturating_sub(initial_storage_usage) as u128);
+        let deposit = env::attached_deposit();
+        require!(
+            deposit >= storage_cost,
+            format!("Must attach at least {} for storage", storage_cost.exact_amount_display())
+        );
+        if deposit > storage_cost {
+            Promise::new(author.clone()).transfer(deposit.saturating_sub(storage_cost));
+        }
+
+        InvestigationEvent::NoteAdded {
+            token_id,
+            author: author.to_string(),
+            index,
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+
+        index + 1
+    }
+
+    /// Lists notes in the order they were written
+    pub fn get_case_notes(&self, token_id: TokenId, from_index: Option<u64>, limit: Option<u64>) -> Vec<CaseNote> {
+        let count = self.case_note_counts.get(&token_id).unwrap_or(0) as u64;
+        let start = from_index.unwrap_or(0).min(count);
+        let end = start.saturating_add(limit.unwrap_or(MAX_NOTES_LIMIT).min(MAX_NOTES_LIMIT)).min(count);
+        (start..end)
+            .filter_map(|index| self.case_notes.get(&(token_id.clone(), index as u32)))
+            .collect()
+    }
+
+    pub fn get_case_note_count(&self, token_id: TokenId) -> u32 {
+        self.case_note_counts.get(&token_id).unwrap_or(0)
+    }
+
+    pub fn get_collaborators(&self, token_id: TokenId) -> Vec<AccountId> {
+        self.collaborators.get(&token_id).unwrap_or_default()
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_push_case_note(&mut self, token_id: &TokenId, note: &CaseNote) {
+        let index = self.case_note_counts.get(token_id).unwrap_or(0);
+        self.case_notes.insert(&(token_id.clone(), index), note);
+        self.case_note_counts.insert(token_id, &(index + 1));
+    }
+
+    /// Drops every note on the case, returning them in the order they were written
+    pub(crate) fn internal_remove_case_notes(&mut self, token_id: &TokenId) -> Vec<CaseNote> {
+        let count = self.case_note_counts.remove(token_id).unwrap_or(0);
+        (0..count)
+            .filter_map(|index| self.case_notes.remove(&(token_id.clone(), index)))
+            .collect()
+    }
+
+    /// The requester is implicitly a collaborator on their own case
+    pub(crate) fn is_collaborator(&self, token_id: &TokenId, account_id: &AccountId) -> bool {
+        self.get_investigation_metadata(token_id.clone())
+            .is_some_and(|investigation| &investigation.requester == account_id)
+            || self.collaborators.get(token_id).is_some_and(|collaborators| collaborators.contains(account_id))
+    }
+
+    fn assert_case_requester(&self, token_id: &TokenId) -> AccountId {
+        let investigation = self.get_investigation_metadata(token_id.clone())
+            .unwrap_or_else(|| env::panic_str("Investigation not found"));
+        require!(
+            env::predecessor_account_id() == investigation.requester,
+            "Only the requester can manage collaborators"
+        );
+        investigation.requester
+    }
+}
@@ -0,0 +1,69 @@
+use std::prelude::v1::*;
+use crate::*;
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::serde::{Deserialize, Serialize};
+
+pub const PRIVATE_CASE_DESCRIPTION: &str = "Private investigation";
+
+/// Non-sensitive view of a case, safe to expose for private investigations
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PublicInvestigationView {
+    pub case_number: u64,
+    pub target_account: AccountId,
+    pub status: InvestigationStatus,
+    pub visibility: Visibility,
+    pub last_updated: U64,
+}
+
+/// Private results must arrive with an `encrypted_payload` and without plaintext
+/// summary or financials, which are rejected rather than silently dropped
+pub fn assert_private_update(metadata: &InvestigationMetadata) {
+    let is_blank = |value: &str| value.is_empty() || value == "0";
+    require!(
+        metadata.summary.is_none() && is_blank(&metadata.total_value_usd) && is_blank(&metadata.near_balance),
+        "Private investigations cannot carry a plaintext summary or financials"
+    );
+    require!(
+        metadata.encrypted_payload.is_some(),
+        "Private investigation updates require an encrypted_payload"
+    );
+}
+
+/// Blanks plaintext findings (summary, financials, activity and risk); private results
+/// only travel as `encrypted_payload`
+pub fn strip_sensitive_fields(metadata: &mut InvestigationMetadata) {
+    metadata.visibility = Visibility::Private;
+    metadata.summary = None;
+    metadata.total_value_usd = String::new();
+    metadata.near_balance = String::new();
+    metadata.transaction_count = 0;
+    metadata.is_bot = false;
+    metadata.risk_level = RiskLevel::Unknown;
+    metadata.risk_inherited_from = None;
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_public_investigation(&self, token_id: TokenId) -> Option<PublicInvestigationView> {
+        let metadata = self.get_investigation_metadata(token_id.clone())?;
+        Some(PublicInvestigationView {
+            case_number: metadata.case_number,
+            target_account: metadata.target_account,
+            status: self.investigation_status.get(&token_id).unwrap_or(metadata.status),
+            visibility: if self.private_cases.contains_key(&token_id) { Visibility::Private } else { Visibility::Public },
+            last_updated: metadata.last_updated,
+        })
+    }
+
+    /// Public key the requester supplied for encrypting a private case's results
+    pub fn get_encryption_public_key(&self, token_id: TokenId) -> Option<Base64VecU8> {
+        self.private_cases.get(&token_id)
+    }
+}
+
+impl Contract {
+    pub(crate) fn is_private_case(&self, token_id: &TokenId) -> bool {
+        self.private_cases.contains_key(token_id)
+    }
+}
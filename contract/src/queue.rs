@@ -0,0 +1,202 @@
+use std::prelude::v1::*;
+use crate::*;
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// 10 minutes
+pub const DEFAULT_JOB_LEASE_NS: u64 = 10 * 60 * 1_000_000_000;
+/// Upper bound on expired leases returned to the queue per claim
+const MAX_LEASE_SWEEP: usize = 20;
+const MAX_QUEUE_LIMIT: u64 = 100;
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JobLease {
+    pub oracle_id: AccountId,
+    pub worker_id: String,
+    pub claimed_at: U64,
+    pub leased_until: U64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JobAssignment {
+    pub token_id: TokenId,
+    pub target_account: AccountId,
+    pub tier: InvestigationTier,
+    pub analysis_depth: u8,
+    pub leased_until: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Leases the next pending case (by tier, then age) to `worker_id` and moves it to `Processing`
+    pub fn claim_next_job(&mut self, worker_id: String) -> Option<JobAssignment> {
+        self.assert_oracle();
+        self.internal_requeue_expired_leases();
+
+        while let Some(position) = self.job_queue.min() {
+            let token_id = self.job_queue.remove(&position)?;
+            if self.investigation_status.get(&token_id) != Some(InvestigationStatus::Pending) {
+                self.job_positions.remove(&token_id);
+                continue;
+            }
+            let investigation = match self.get_investigation_metadata(token_id.clone()) {
+                Some(investigation) => investigation,
+                None => {
+                    self.job_positions.remove(&token_id);
+                    continue;
+                }
+            };
+
+            self.internal_set_status(&token_id, InvestigationStatus::Processing);
+            let lease = JobLease {
+                oracle_id: env::predecessor_account_id(),
+                worker_id,
+                claimed_at: U64(env::block_timestamp()),
+                leased_until: U64(env::block_timestamp() + self.job_lease_duration_ns.0),
+            };
+            self.internal_insert_lease(&token_id, &lease);
+
+            InvestigationEvent::JobClaimed {
+                token_id: token_id.clone(),
+                oracle_id: lease.oracle_id.to_string(),
+                worker_id: lease.worker_id.clone(),
+                leased_until: lease.leased_until,
+            }.log();
+
+            let tier = self.case_tiers.get(&token_id).unwrap_or_default();
+            return Some(JobAssignment {
+                analysis_depth: self.tier_config(&tier).analysis_depth,
+                tier,
+                token_id,
+                target_account: investigation.target_account,
+                leased_until: lease.leased_until,
+            });
+        }
+        None
+    }
+
+    /// Extends the caller's lease on `token_id`
+    pub fn heartbeat(&mut self, token_id: TokenId) -> U64 {
+        let mut lease = self.assert_lease_holder(&token_id);
+        self.internal_remove_lease(&token_id);
+        lease.leased_until = U64(env::block_timestamp() + self.job_lease_duration_ns.0);
+        self.internal_insert_lease(&token_id, &lease);
+        lease.leased_until
+    }
+
+    /// Gives a leased case back to the queue
+    pub fn release_job(&mut self, token_id: TokenId) {
+        self.assert_lease_holder(&token_id);
+        self.internal_set_status(&token_id, InvestigationStatus::Pending);
+        InvestigationEvent::JobReleased {
+            token_id,
+            reason: "released".to_string(),
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+    }
+
+    pub fn set_job_lease_duration(&mut self, duration_ns: U64) {
+        self.assert_owner();
+        require!(duration_ns.0 > 0, "Lease duration must be positive");
+        self.job_lease_duration_ns = duration_ns;
+    }
+
+    pub fn get_job_lease(&self, token_id: TokenId) -> Option<JobLease> {
+        self.job_leases.get(&token_id)
+    }
+
+    pub fn get_job_queue(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<TokenId> {
+        let limit = limit.unwrap_or(MAX_QUEUE_LIMIT).min(MAX_QUEUE_LIMIT) as usize;
+        self.job_queue
+            .iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit)
+            .map(|(_, token_id)| token_id)
+            .collect()
+    }
+
+    pub fn get_job_queue_length(&self) -> u64 {
+        self.job_queue.len()
+    }
+}
+
+impl Contract {
+    /// Keeps the queue and leases consistent with a case's status: pending cases are
+    /// queued, anything else is taken off the queue and finished cases lose their lease
+    pub(crate) fn internal_sync_job(&mut self, token_id: &TokenId, status: &InvestigationStatus) {
+        if self.has_active_assignment(token_id) {
+            self.internal_remove_job(token_id);
+            return;
+        }
+        match status {
+            InvestigationStatus::Pending => {
+                self.internal_remove_lease(token_id);
+                let position = match self.job_positions.get(token_id) {
+                    Some(position) => position,
+                    None => {
+                        let rank = self.case_tiers.get(token_id).unwrap_or_default().queue_rank();
+                        let position = (rank, self.next_job_position);
+                        self.next_job_position += 1;
+                        self.job_positions.insert(token_id, &position);
+                        position
+                    }
+                };
+                self.job_queue.insert(&position, token_id);
+            }
+            InvestigationStatus::Processing => {
+                if let Some(position) = self.job_positions.get(token_id) {
+                    self.job_queue.remove(&position);
+                }
+            }
+            _ => self.internal_remove_job(token_id),
+        }
+    }
+
+    pub(crate) fn internal_remove_job(&mut self, token_id: &TokenId) {
+        if let Some(position) = self.job_positions.remove(token_id) {
+            self.job_queue.remove(&position);
+        }
+        self.internal_remove_lease(token_id);
+    }
+
+    fn internal_insert_lease(&mut self, token_id: &TokenId, lease: &JobLease) {
+        self.job_leases.insert(token_id, lease);
+        self.leases_by_expiry.insert(&(lease.leased_until.0, token_id.clone()), &());
+    }
+
+    fn internal_remove_lease(&mut self, token_id: &TokenId) {
+        if let Some(lease) = self.job_leases.remove(token_id) {
+            self.leases_by_expiry.remove(&(lease.leased_until.0, token_id.clone()));
+        }
+    }
+
+    fn internal_requeue_expired_leases(&mut self) {
+        let now = env::block_timestamp();
+        let expired: Vec<TokenId> = self.leases_by_expiry
+            .iter()
+            .take_while(|((leased_until, _), _)| *leased_until <= now)
+            .take(MAX_LEASE_SWEEP)
+            .map(|((_, token_id), _)| token_id)
+            .collect();
+        for token_id in expired {
+            self.internal_set_status(&token_id, InvestigationStatus::Pending);
+            InvestigationEvent::JobReleased {
+                token_id,
+                reason: "lease expired".to_string(),
+                timestamp: U64(now),
+            }.log();
+        }
+    }
+
+    fn assert_lease_holder(&self, token_id: &TokenId) -> JobLease {
+        let lease = self.job_leases.get(token_id)
+            .unwrap_or_else(|| env::panic_str("No active lease for this case"));
+        let caller = env::predecessor_account_id();
+        require!(
+            caller == lease.oracle_id || caller == self.owner_id,
+            "Only the lease holder can manage this job"
+        );
+        lease
+    }
+}
@@ -0,0 +1,127 @@
+use std::prelude::v1::*;
+use crate::*;
+use near_sdk::serde::{Deserialize, Serialize};
+
+pub const MAX_RATING_COMMENT_LENGTH: usize = 280;
+/// 7 days
+pub const RATING_EDIT_WINDOW_NS: u64 = 7 * 24 * 60 * 60 * 1_000_000_000;
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CaseRating {
+    pub rater: AccountId,
+    pub rating: u8,
+    pub comment: Option<String>,
+    pub created_at: U64,
+    pub updated_at: U64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RatingStats {
+    pub rating_count: u32,
+    pub rating_total: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Rates a completed case from 1 to 5. Whoever paid for the case may edit their rating
+    /// within `RATING_EDIT_WINDOW_NS` of first submitting it; storage growth is paid by the caller.
+    #[payable]
+    pub fn rate_investigation(&mut self, token_id: TokenId, rating: u8, comment: Option<String>) -> CaseRating {
+        let rater = env::predecessor_account_id();
+        let investigation = self.get_investigation_metadata(token_id.clone())
+            .unwrap_or_else(|| env::panic_str("Investigation not found"));
+        let payer = self.case_payers.get(&token_id).unwrap_or(investigation.requester);
+        require!(rater == payer, "Only the payer can rate a case");
+        // Those who produced the result cannot rate their own work
+        let is_analyst = self.analyst_assignments.get(&token_id)
+            .is_some_and(|assignment| assignment.analyst_id == rater);
+        let is_resolver = self.case_resolutions.get(&token_id)
+            .is_some_and(|resolution| resolution.resolvers.contains(&rater));
+        require!(!is_analyst && !is_resolver, "The analyst or resolvers cannot rate their own case");
+        require!(
+            self.investigation_status.get(&token_id) == Some(InvestigationStatus::Completed),
+            "Only completed cases can be rated"
+        );
+        require!((1..=5).contains(&rating), "Rating must be between 1 and 5");
+        if let Some(comment) = &comment {
+            require!(comment.len() <= MAX_RATING_COMMENT_LENGTH, "Rating comment too long");
+        }
+        let initial_storage_usage = env::storage_usage();
+        let now = env::block_timestamp();
+
+        let previous = self.case_ratings.get(&token_id);
+        let created_at = match &previous {
+            Some(previous) => {
+                require!(
+                    now < previous.created_at.0 + RATING_EDIT_WINDOW_NS,
+                    "Rating can no longer be edited"
+                );
+                previous.created_at
+            }
+            None => U64(now),
+        };
+        let case_rating = CaseRating {
+            rater: rater.clone(),
+            rating,
+            comment,
+            created_at,
+            updated_at: U64(now),
+        };
+        self.case_ratings.insert(&token_id, &case_rating);
+
+        let previous_rating = previous.map(|previous| previous.rating);
+        self.internal_apply_rating(&token_id, previous_rating, rating);
+
+        let storage_cost = env::storage_byte_cost()
+            .saturating_mul(env::storage_usage().saturating_sub(initial_storage_usage) as u128);
+        let deposit = env::attached_deposit();
+        require!(
+            deposit >= storage_cost,
+            format!("Must attach at least {} for storage", storage_cost.exact_amount_display())
+        );
+        if deposit > storage_cost {
+            Promise::new(rater.clone()).transfer(deposit.saturating_sub(storage_cost));
+        }
+
+        InvestigationEvent::CaseRated {
+            token_id,
+            rater: rater.to_string(),
+            rating,
+            edited: previous_rating.is_some(),
+            timestamp: U64(now),
+        }.log();
+
+        case_rating
+    }
+
+    pub fn get_case_rating(&self, token_id: TokenId) -> Option<CaseRating> {
+        self.case_ratings.get(&token_id)
+    }
+
+    pub fn get_rating_stats(&self) -> RatingStats {
+        self.rating_stats.clone()
+    }
+}
+
+impl Contract {
+    /// Folds a new or edited rating into the contract totals and the case producers' reputation
+    fn internal_apply_rating(&mut self, token_id: &TokenId, previous: Option<u8>, rating: u8) {
+        let added_count = if previous.is_some() { 0 } else { 1 };
+        let removed = previous.unwrap_or(0) as u32;
+
+        self.rating_stats.rating_count += added_count;
+        self.rating_stats.rating_total = U64(self.rating_stats.rating_total.0.saturating_sub(removed as u64) + rating as u64);
+
+        let resolvers = self.case_resolutions.get(token_id)
+            .map(|resolution| resolution.resolvers)
+            .unwrap_or_default();
+        for resolver in resolvers {
+            self.internal_update_reputation(&resolver, |reputation| {
+                reputation.rating_count += added_count;
+                reputation.rating_total = reputation.rating_total.saturating_sub(removed) + rating as u32;
+            });
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use std::prelude::v1::*;
+use crate::*;
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::serde::{Deserialize, Serialize};
+
+pub const REDACTED_PLACEHOLDER: &str = "[redacted]";
+const MAX_OPT_OUT_LIMIT: u64 = 100;
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum RedactableField {
+    Summary,
+    TotalValueUsd,
+    NearBalance,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RedactionRecord {
+    pub fields: Vec<RedactableField>,
+    pub reason: String,
+    /// sha256 of the JSON encoded values that were removed
+    pub removed_hash: Base64VecU8,
+    pub redacted_by: AccountId,
+    pub redacted_at: U64,
+}
+
+/// Blanks `fields` on `metadata`, returning the removed values
+pub fn redact_fields(metadata: &mut InvestigationMetadata, fields: &[RedactableField]) -> Vec<Option<String>> {
+    fields
+        .iter()
+        .map(|field| match field {
+            RedactableField::Summary => metadata.summary.take(),
+            RedactableField::TotalValueUsd => {
+                Some(std::mem::replace(&mut metadata.total_value_usd, REDACTED_PLACEHOLDER.to_string()))
+            }
+            RedactableField::NearBalance => {
+                Some(std::mem::replace(&mut metadata.near_balance, REDACTED_PLACEHOLDER.to_string()))
+            }
+        })
+        .collect()
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn redact_investigation(
+        &mut self,
+        token_id: TokenId,
+        fields: Vec<RedactableField>,
+        reason: String,
+    ) -> RedactionRecord {
+        self.assert_moderator();
+        require!(!fields.is_empty(), "No fields to redact");
+        require!(!reason.is_empty(), "A reason is required");
+        let mut metadata = self.get_investigation_metadata(token_id.clone())
+            .unwrap_or_else(|| env::panic_str("Investigation not found"));
+
+        let removed = redact_fields(&mut metadata, &fields);
+        let removed_bytes = serde_json::to_vec(&removed).unwrap_or_default();
+        metadata.redacted = true;
+        metadata.last_updated = U64(env::block_timestamp());
+        self.investigation_data.insert(&token_id, &metadata);
+        self.internal_redact_submissions(&token_id, &fields);
+
+        let extra = serde_json::to_string(&metadata).unwrap_or_default();
+        self.update_token_metadata(&token_id, |token_metadata| {
+            token_metadata.description = Some(REDACTED_PLACEHOLDER.to_string());
+            token_metadata.extra = Some(extra);
+            token_metadata.updated_at = Some(env::block_timestamp().to_string());
+        });
+        self.internal_refresh_artwork(&token_id, &metadata);
+
+        let record = RedactionRecord {
+            fields,
+            reason,
+            removed_hash: Base64VecU8::from(env::sha256(&removed_bytes)),
+            redacted_by: env::predecessor_account_id(),
+            redacted_at: U64(env::block_timestamp()),
+        };
+        let mut redactions = self.redactions.get(&token_id).unwrap_or_default();
+        redactions.push(record.clone());
+        self.redactions.insert(&token_id, &redactions);
+
+        InvestigationEvent::InvestigationRedacted {
+            token_id,
+            redacted_by: record.redacted_by.to_string(),
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+
+        record
+    }
+
+    pub fn get_redactions(&self, token_id: TokenId) -> Vec<RedactionRecord> {
+        self.redactions.get(&token_id).unwrap_or_default()
+    }
+
+    /// Blocks `start_investigation` for `account_id` unless the caller holds a privileged role.
+    /// Accounts can opt themselves out; moderators can do it on their behalf.
+    pub fn add_opt_out(&mut self, account_id: AccountId) {
+        self.assert_self_or_moderator(&account_id);
+        self.opted_out_accounts.insert(&account_id);
+        self.internal_refresh_target_artwork(&account_id);
+    }
+
+    pub fn remove_opt_out(&mut self, account_id: AccountId) {
+        self.assert_self_or_moderator(&account_id);
+        self.opted_out_accounts.remove(&account_id);
+        self.internal_refresh_target_artwork(&account_id);
+    }
+
+    pub fn is_opted_out(&self, account_id: AccountId) -> bool {
+        self.opted_out_accounts.contains(&account_id)
+    }
+
+    pub fn get_opted_out_accounts(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<AccountId> {
+        let limit = limit.unwrap_or(MAX_OPT_OUT_LIMIT).min(MAX_OPT_OUT_LIMIT) as usize;
+        self.opted_out_accounts
+            .iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit)
+            .collect()
+    }
+}
+
+impl Contract {
+    /// Re-applies earlier redactions so later pipeline updates cannot restore removed values
+    pub(crate) fn apply_redactions(&self, token_id: &TokenId, metadata: &mut InvestigationMetadata) {
+        if let Some(redactions) = self.redactions.get(token_id) {
+            for redaction in redactions.iter() {
+                redact_fields(metadata, &redaction.fields);
+            }
+            metadata.redacted = true;
+        }
+    }
+
+    /// Oracle submissions hold their own copy of the result; attestations only keep
+    /// the signature and status, so there is nothing else to redact
+    pub(crate) fn internal_redact_submissions(&mut self, token_id: &TokenId, fields: &[RedactableField]) {
+        if let Some(mut submissions) = self.oracle_submissions.get(token_id) {
+            for submission in submissions.iter_mut() {
+                redact_fields(&mut submission.metadata, fields);
+                submission.metadata.redacted = true;
+            }
+            self.oracle_submissions.insert(token_id, &submissions);
+        }
+    }
+
+    /// Shows or hides the target on the card of its existing case after an opt-out change
+    fn internal_refresh_target_artwork(&mut self, account_id: &AccountId) {
+        if let Some(token_id) = self.investigated_accounts.get(account_id) {
+            if let Some(investigation) = self.get_investigation_metadata(token_id.clone()) {
+                self.internal_refresh_artwork(&token_id, &investigation);
+            }
+        }
+    }
+
+    fn assert_self_or_moderator(&self, account_id: &AccountId) {
+        let caller = env::predecessor_account_id();
+        if &caller != account_id {
+            self.assert_moderator();
+        }
+    }
+
+    pub(crate) fn is_privileged(&self, account_id: &AccountId) -> bool {
+        self.is_oracle(account_id) || self.moderators.contains(account_id)
+    }
+}
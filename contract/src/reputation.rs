@@ -0,0 +1,114 @@
+use std::prelude::v1::*;
+use crate::*;
+use near_sdk::serde::{Deserialize, Serialize};
+
+const MAX_LEADERBOARD_LIMIT: u64 = 100;
+const POINTS_PER_COMPLETED_CASE: i64 = 10;
+const POINTS_PER_DISPUTE_LOST: i64 = 30;
+/// Each rating adds `2 * (rating - 3)` points, so a neutral 3 leaves the score unchanged
+const POINTS_PER_RATING_STEP: i64 = 2;
+const NEUTRAL_RATING: i64 = 3;
+
+/// Track record of a result producer (oracle or marketplace analyst)
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Reputation {
+    pub completed_cases: u32,
+    pub disputes_lost: u32,
+    pub total_turnaround_ns: U64,
+    pub rating_count: u32,
+    pub rating_total: u32,
+}
+
+impl Reputation {
+    pub fn score(&self) -> u32 {
+        let score = self.completed_cases as i64 * POINTS_PER_COMPLETED_CASE
+            - self.disputes_lost as i64 * POINTS_PER_DISPUTE_LOST
+            + (self.rating_total as i64 - self.rating_count as i64 * NEUTRAL_RATING) * POINTS_PER_RATING_STEP;
+        score.clamp(0, u32::MAX as i64) as u32
+    }
+
+    pub fn average_turnaround_ns(&self) -> U64 {
+        U64(self.total_turnaround_ns.0.checked_div(self.completed_cases as u64).unwrap_or(0))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReputationView {
+    pub account_id: AccountId,
+    pub completed_cases: u32,
+    pub disputes_lost: u32,
+    pub average_turnaround_ns: U64,
+    pub rating_count: u32,
+    pub rating_total: u32,
+    pub score: u32,
+}
+
+impl ReputationView {
+    fn new(account_id: AccountId, reputation: &Reputation) -> Self {
+        Self {
+            account_id,
+            completed_cases: reputation.completed_cases,
+            disputes_lost: reputation.disputes_lost,
+            average_turnaround_ns: reputation.average_turnaround_ns(),
+            rating_count: reputation.rating_count,
+            rating_total: reputation.rating_total,
+            score: reputation.score(),
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_reputation(&self, account_id: AccountId) -> ReputationView {
+        let reputation = self.reputations.get(&account_id).unwrap_or_default();
+        ReputationView::new(account_id, &reputation)
+    }
+
+    /// Producers ordered by descending score
+    pub fn get_leaderboard(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<ReputationView> {
+        let limit = limit.unwrap_or(MAX_LEADERBOARD_LIMIT).min(MAX_LEADERBOARD_LIMIT) as usize;
+        self.reputation_ranking
+            .iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit)
+            .filter_map(|((_, account_id), _)| {
+                self.reputations.get(&account_id)
+                    .map(|reputation| ReputationView::new(account_id, &reputation))
+            })
+            .collect()
+    }
+}
+
+impl Contract {
+    /// Applies `update` to a producer's record and keeps the leaderboard ordering in sync
+    pub(crate) fn internal_update_reputation<F: FnOnce(&mut Reputation)>(&mut self, account_id: &AccountId, update: F) {
+        let mut reputation = self.reputations.get(account_id).unwrap_or_default();
+        self.reputation_ranking.remove(&ranking_key(account_id, &reputation));
+        update(&mut reputation);
+        self.reputation_ranking.insert(&ranking_key(account_id, &reputation), &reputation.score());
+        self.reputations.insert(account_id, &reputation);
+    }
+
+    /// Credits each resolver with a completed case. Turnaround is measured from the minted
+    /// `investigation_date`, read from the token's `issued_at` since results overwrite the metadata.
+    pub(crate) fn internal_credit_completion(&mut self, token_id: &TokenId, resolvers: &[AccountId]) {
+        let opened_at = self.get_token_metadata(token_id.clone())
+            .and_then(|metadata| metadata.issued_at)
+            .and_then(|issued_at| issued_at.parse::<u64>().ok())
+            .unwrap_or_else(env::block_timestamp);
+        let turnaround = env::block_timestamp().saturating_sub(opened_at);
+        for resolver in resolvers {
+            self.internal_update_reputation(resolver, |reputation| {
+                reputation.completed_cases += 1;
+                reputation.total_turnaround_ns = U64(reputation.total_turnaround_ns.0.saturating_add(turnaround));
+            });
+        }
+    }
+}
+
+/// Ranking keys sort ascending, so the score is inverted to list the best producers first
+fn ranking_key(account_id: &AccountId, reputation: &Reputation) -> (u32, AccountId) {
+    (u32::MAX - reputation.score(), account_id.clone())
+}
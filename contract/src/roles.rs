@@ -0,0 +1,45 @@
+use std::prelude::v1::*;
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    pub fn add_moderator(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.moderators.insert(&account_id);
+    }
+
+    pub fn remove_moderator(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.moderators.remove(&account_id);
+    }
+
+    pub fn is_moderator(&self, account_id: AccountId) -> bool {
+        self.moderators.contains(&account_id)
+    }
+
+    pub fn get_moderators(&self) -> Vec<AccountId> {
+        self.moderators.to_vec()
+    }
+}
+
+impl Contract {
+    /// The owner acts as the default oracle alongside any staked oracles
+    pub(crate) fn is_oracle(&self, account_id: &AccountId) -> bool {
+        account_id == &self.owner_id || self.is_staked_oracle(account_id)
+    }
+
+    pub(crate) fn assert_owner(&self) {
+        require!(
+            env::predecessor_account_id() == self.owner_id,
+            "Only the contract owner can call this method"
+        );
+    }
+
+    pub(crate) fn assert_moderator(&self) {
+        let caller = env::predecessor_account_id();
+        require!(
+            caller == self.owner_id || self.moderators.contains(&caller),
+            "Only a moderator can call this method"
+        );
+    }
+}
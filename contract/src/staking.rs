@@ -0,0 +1,223 @@
+use std::prelude::v1::*;
+use crate::*;
+use near_sdk::serde::{Deserialize, Serialize};
+
+pub const DEFAULT_MIN_ORACLE_STAKE: NearToken = NearToken::from_near(10);
+/// 7 days
+pub const DEFAULT_UNBONDING_PERIOD_NS: u64 = 7 * 24 * 60 * 60 * 1_000_000_000;
+pub const DEFAULT_DISPUTE_SLASH: NearToken = NearToken::from_near(1);
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakingConfig {
+    pub min_oracle_stake: NearToken,
+    pub unbonding_period_ns: U64,
+    /// Taken from each resolver's stake when a dispute on their result is upheld
+    pub dispute_slash: NearToken,
+}
+
+impl Default for StakingConfig {
+    fn default() -> Self {
+        Self {
+            min_oracle_stake: DEFAULT_MIN_ORACLE_STAKE,
+            unbonding_period_ns: U64(DEFAULT_UNBONDING_PERIOD_NS),
+            dispute_slash: DEFAULT_DISPUTE_SLASH,
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OracleStake {
+    pub staked: NearToken,
+    pub unbonding: NearToken,
+    pub unbonding_ends_at: Option<U64>,
+    pub total_slashed: NearToken,
+}
+
+impl Default for OracleStake {
+    fn default() -> Self {
+        Self {
+            staked: NearToken::from_yoctonear(0),
+            unbonding: NearToken::from_yoctonear(0),
+            unbonding_ends_at: None,
+            total_slashed: NearToken::from_yoctonear(0),
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Registers the caller as an oracle, or tops up an existing stake
+    #[payable]
+    pub fn register_oracle(&mut self) -> OracleStake {
+        let oracle_id = env::predecessor_account_id();
+        let deposit = env::attached_deposit();
+        require!(!deposit.is_zero(), "Must attach a stake");
+        require!(!self.removed_oracles.contains(&oracle_id), "Oracle has been removed");
+
+        let mut stake = self.oracle_stakes.get(&oracle_id).unwrap_or_default();
+        stake.staked = stake.staked.saturating_add(deposit);
+        require!(
+            stake.staked >= self.staking_config.min_oracle_stake,
+            format!("Stake must be at least {}", self.staking_config.min_oracle_stake.exact_amount_display())
+        );
+        self.oracle_stakes.insert(&oracle_id, &stake);
+        self.oracles.insert(&oracle_id);
+
+        InvestigationEvent::OracleRegistered {
+            oracle_id: oracle_id.to_string(),
+            staked: stake.staked,
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+
+        stake
+    }
+
+    /// Starts unbonding `amount`; dropping below the minimum stake deregisters the oracle
+    pub fn unstake(&mut self, amount: NearToken) -> OracleStake {
+        let oracle_id = env::predecessor_account_id();
+        let mut stake = self.oracle_stakes.get(&oracle_id)
+            .unwrap_or_else(|| env::panic_str("No stake found"));
+        require!(!amount.is_zero() && amount <= stake.staked, "Invalid unstake amount");
+
+        stake.staked = stake.staked.saturating_sub(amount);
+        stake.unbonding = stake.unbonding.saturating_add(amount);
+        stake.unbonding_ends_at = Some(U64(env::block_timestamp() + self.staking_config.unbonding_period_ns.0));
+        self.oracle_stakes.insert(&oracle_id, &stake);
+        if stake.staked < self.staking_config.min_oracle_stake {
+            self.oracles.remove(&oracle_id);
+        }
+
+        InvestigationEvent::OracleUnstaked {
+            oracle_id: oracle_id.to_string(),
+            amount,
+            unbonding_ends_at: stake.unbonding_ends_at.unwrap(),
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+
+        stake
+    }
+
+    pub fn withdraw_stake(&mut self) -> Promise {
+        let oracle_id = env::predecessor_account_id();
+        let mut stake = self.oracle_stakes.get(&oracle_id)
+            .unwrap_or_else(|| env::panic_str("No stake found"));
+        require!(!stake.unbonding.is_zero(), "Nothing to withdraw");
+        require!(
+            stake.unbonding_ends_at.is_some_and(|ends_at| env::block_timestamp() >= ends_at.0),
+            "Stake is still unbonding"
+        );
+
+        let amount = stake.unbonding;
+        stake.unbonding = NearToken::from_yoctonear(0);
+        stake.unbonding_ends_at = None;
+        if stake.staked.is_zero() {
+            self.oracle_stakes.remove(&oracle_id);
+        } else {
+            self.oracle_stakes.insert(&oracle_id, &stake);
+        }
+
+        InvestigationEvent::OracleStakeWithdrawn {
+            oracle_id: oracle_id.to_string(),
+            amount,
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+
+        Promise::new(oracle_id).transfer(amount)
+    }
+
+    /// Governance penalty; takes from the active stake first, then from unbonding funds
+    pub fn slash_oracle(&mut self, oracle_id: AccountId, amount: NearToken, reason: String) -> OracleStake {
+        self.assert_owner();
+        let mut stake = self.oracle_stakes.get(&oracle_id)
+            .unwrap_or_else(|| env::panic_str("No stake found"));
+        self.internal_slash(&oracle_id, &mut stake, amount, reason);
+        stake
+    }
+
+    /// Sends slashed stake to the treasury (the owner); withdraws everything when `amount` is omitted
+    pub fn withdraw_slashed_balance(&mut self, amount: Option<NearToken>) -> Promise {
+        self.assert_owner();
+        let amount = amount.unwrap_or(self.slashed_balance);
+        require!(!amount.is_zero() && amount <= self.slashed_balance, "Invalid withdrawal amount");
+        self.slashed_balance = self.slashed_balance.saturating_sub(amount);
+
+        InvestigationEvent::SlashedBalanceWithdrawn {
+            receiver_id: self.owner_id.to_string(),
+            amount,
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+
+        Promise::new(self.owner_id.clone()).transfer(amount)
+    }
+
+    pub fn set_staking_config(&mut self, config: StakingConfig) {
+        self.assert_owner();
+        self.staking_config = config;
+    }
+
+    pub fn get_staking_config(&self) -> StakingConfig {
+        self.staking_config.clone()
+    }
+
+    pub fn get_oracle_stake(&self, oracle_id: AccountId) -> Option<OracleStake> {
+        self.oracle_stakes.get(&oracle_id)
+    }
+
+    pub fn get_slashed_balance(&self) -> NearToken {
+        self.slashed_balance
+    }
+}
+
+impl Contract {
+    /// Whether `account_id` is registered and currently meets the minimum stake
+    pub(crate) fn is_staked_oracle(&self, account_id: &AccountId) -> bool {
+        self.oracles.contains(account_id)
+            && self.oracle_stakes.get(account_id)
+                .is_some_and(|stake| stake.staked >= self.staking_config.min_oracle_stake)
+    }
+
+    pub(crate) fn internal_slash(
+        &mut self,
+        oracle_id: &AccountId,
+        stake: &mut OracleStake,
+        amount: NearToken,
+        reason: String,
+    ) -> NearToken {
+        let from_staked = amount.min(stake.staked);
+        let from_unbonding = amount.saturating_sub(from_staked).min(stake.unbonding);
+        stake.staked = stake.staked.saturating_sub(from_staked);
+        stake.unbonding = stake.unbonding.saturating_sub(from_unbonding);
+
+        let slashed = from_staked.saturating_add(from_unbonding);
+        stake.total_slashed = stake.total_slashed.saturating_add(slashed);
+        self.slashed_balance = self.slashed_balance.saturating_add(slashed);
+        self.oracle_stakes.insert(oracle_id, stake);
+        if stake.staked < self.staking_config.min_oracle_stake {
+            self.oracles.remove(oracle_id);
+        }
+
+        InvestigationEvent::OracleSlashed {
+            oracle_id: oracle_id.to_string(),
+            amount: slashed,
+            reason,
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+
+        slashed
+    }
+
+    /// Slashes every staked oracle that resolved a case whose dispute was upheld
+    pub(crate) fn internal_slash_resolvers(&mut self, token_id: &TokenId, resolvers: &[AccountId]) {
+        let amount = self.staking_config.dispute_slash;
+        if amount.is_zero() {
+            return;
+        }
+        for resolver in resolvers {
+            if let Some(mut stake) = self.oracle_stakes.get(resolver) {
+                self.internal_slash(resolver, &mut stake, amount, format!("Dispute upheld on {}", token_id));
+            }
+        }
+    }
+}
@@ -1,10 +1,12 @@
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use crate::*;
+    use near_contract_standards::non_fungible_token::metadata::NFT_METADATA_SPEC;
     use near_sdk::test_utils::{accounts, VMContextBuilder};
-    use near_sdk::{testing_env, AccountId, NearToken};
+    use near_sdk::json_types::U64;
+    use near_sdk::{testing_env, AccountId, CurveType, NearToken, PublicKey};
 
-    const MINT_STORAGE_COST: NearToken = NearToken::from_yoctonear(10_000_000_000_000_000_000_000);
+    const MINT_STORAGE_COST: NearToken = NearToken::from_millinear(100);
 
     fn get_context(predecessor_account_id: AccountId) -> VMContextBuilder {
         let mut builder = VMContextBuilder::new();
@@ -21,17 +23,20 @@ mod tests {
         let context = get_context(accounts(1));
         testing_env!(context.build());
         
-        let mut contract = Contract::new(accounts(1));
+        let mut contract = Contract::new(accounts(1), None);
         
         let invalid_metadata = MetadataUpdate {
             description: Some("Test".to_string()),
             extra: "{invalid_json}".to_string(),
+            reference: None,
+            reference_hash: None,
         };
         
         let result = contract.update_investigation_metadata(
             "1".to_string(),
             invalid_metadata,
-            WebhookType::Progress
+            WebhookType::Progress,
+            None
         );
         
         assert!(result.is_err());
@@ -42,21 +47,24 @@ mod tests {
         let context = get_context(accounts(1));
         testing_env!(context.build());
         
-        let mut contract = Contract::new(accounts(1));
+        let mut contract = Contract::new(accounts(1), None);
         let target = accounts(2);
-        contract.start_investigation(target).unwrap();
+        contract.start_investigation(target.clone(), None).unwrap();
         
         let metadata = InvestigationMetadata::new(1, target, accounts(1));
         let update = MetadataUpdate {
             description: Some("Test".to_string()),
             extra: serde_json::to_string(&metadata).unwrap(),
+            reference: None,
+            reference_hash: None,
         };
         
         // Test with invalid webhook type
         let result = contract.update_investigation_metadata(
             "1".to_string(),
             update,
-            WebhookType::Log // Log type shouldn't update metadata
+            WebhookType::Log, // Log type shouldn't update metadata
+            None
         );
         
         assert!(result.is_err());
@@ -67,7 +75,7 @@ mod tests {
         let context = get_context(accounts(1));
         testing_env!(context.build());
         
-        let mut contract = Contract::new(accounts(1));
+        let mut contract = Contract::new(accounts(1), None);
         
         // Test with insufficient deposit
         let context = get_context(accounts(1))
@@ -75,7 +83,1614 @@ mod tests {
             .build();
         testing_env!(context);
         
-        let result = contract.start_investigation(accounts(2));
+        let result = contract.start_investigation(accounts(2), None);
         assert!(result.is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_record_edges_and_neighbors() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let response = contract.start_investigation(accounts(2), None).unwrap();
+
+        let edges = vec![
+            EdgeInput {
+                from_account: accounts(2),
+                to_account: accounts(3),
+                relation_kind: RelationKind::Transfer,
+                weight: 5,
+            },
+            EdgeInput {
+                from_account: accounts(3),
+                to_account: accounts(4),
+                relation_kind: RelationKind::Funding,
+                weight: 1,
+            },
+        ];
+        assert_eq!(contract.record_edges(response.request_id.clone(), edges), 2);
+
+        assert_eq!(contract.get_edges_from(accounts(2), None, None).len(), 1);
+        assert_eq!(contract.get_edges_to(accounts(4), None, None).len(), 1);
+        assert_eq!(contract.get_neighbors(accounts(2), Some(1), None, None).len(), 1);
+
+        let neighbors = contract.get_neighbors(accounts(2), Some(2), None, None);
+        assert_eq!(neighbors.len(), 2);
+        assert_eq!(neighbors[1].account_id, accounts(4));
+        assert_eq!(neighbors[1].depth, 2);
+    }
+
+    #[test]
+    fn test_add_evidence_records_manifest() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        testing_env!(get_context(accounts(3)).build());
+        let token_id = contract.start_investigation(accounts(2), None).unwrap().request_id;
+
+        let count = contract.add_evidence(
+            token_id.clone(),
+            EvidenceKind::CsvExport,
+            "ipfs://bafy-transfers".to_string(),
+            "a".repeat(64),
+            Some("Outgoing transfers".to_string()),
+            Some("ipfs://bafy-manifest".to_string()),
+        );
+        assert_eq!(count, 1);
+        assert_eq!(contract.get_evidence(token_id.clone())[0].added_by, accounts(3));
+
+        let record = contract.get_evidence_manifest_record(token_id.clone()).unwrap();
+        assert_eq!(record.uri, Some("ipfs://bafy-manifest".to_string()));
+        assert_eq!(record.hash, contract.evidence_manifest_hash(&token_id));
+        assert!(contract.get_token_metadata(token_id.clone()).unwrap().reference.is_none());
+
+        // Later evidence re-hashes the manifest without needing a new URI
+        contract.add_evidence(token_id.clone(), EvidenceKind::Screenshot, "ipfs://bafy-shot".to_string(), "b".repeat(64), None, None);
+        let updated = contract.get_evidence_manifest_record(token_id.clone()).unwrap();
+        assert_eq!(updated.uri, record.uri);
+        assert_ne!(updated.hash, record.hash);
+        assert_eq!(updated.hash, contract.evidence_manifest_hash(&token_id));
+    }
+
+    #[test]
+    #[should_panic(expected = "sha256 must be 64 hex characters")]
+    fn test_add_evidence_rejects_bad_hash() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let token_id = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        contract.add_evidence(token_id, EvidenceKind::Other, "ipfs://x".to_string(), "not-a-hash".to_string(), None, None);
+    }
+
+    #[test]
+    fn test_metadata_update_reference_validation() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut update = MetadataUpdate {
+            description: None,
+            extra: "{}".to_string(),
+            reference: Some("ipfs://bafy-report".to_string()),
+            reference_hash: None,
+        };
+        assert!(update.validate(None).is_err());
+
+        update.reference_hash = Some(vec![0u8; 32].into());
+        assert!(update.validate(None).is_ok());
+        assert!(update.validate(Some(1)).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid attestation signature")]
+    fn test_relayed_update_requires_valid_signature() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let token_id = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        let public_key = PublicKey::from_parts(CurveType::ED25519, vec![7u8; 32]).unwrap();
+        contract.add_attestation_key(public_key.clone());
+
+        // Any relayer may submit, but the signature must verify
+        testing_env!(get_context(accounts(4)).build());
+        let metadata = InvestigationMetadata::new(1, accounts(2), accounts(1));
+        let update = MetadataUpdate {
+            description: None,
+            extra: serde_json::to_string(&metadata).unwrap(),
+            reference: None,
+            reference_hash: None,
+        };
+        let _ = contract.update_investigation_metadata(
+            token_id,
+            update,
+            WebhookType::Completion,
+            Some(SignedAttestation { public_key, signature: vec![0u8; 64].into(), nonce: U64(1) }),
+        );
+    }
+
+    #[test]
+    fn test_consensus_finalizes_on_quorum() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let token_id = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        for oracle in [accounts(3), accounts(4), accounts(5)] {
+            testing_env!(get_context(oracle).attached_deposit(DEFAULT_MIN_ORACLE_STAKE).build());
+            contract.register_oracle();
+        }
+        testing_env!(get_context(accounts(1)).build());
+        contract.set_consensus_config(ConsensusConfig {
+            enabled: true,
+            quorum: 2,
+            transaction_count_tolerance_bps: 1_000,
+        });
+
+        let submit = |contract: &mut Contract, oracle: AccountId, transaction_count: u64| {
+            testing_env!(get_context(oracle).build());
+            let mut metadata = InvestigationMetadata::new(1, accounts(2), accounts(1));
+            metadata.transaction_count = transaction_count;
+            metadata.risk_level = RiskLevel::High;
+            contract.submit_result(token_id.clone(), MetadataUpdate {
+                description: None,
+                extra: serde_json::to_string(&metadata).unwrap(),
+                reference: None,
+                reference_hash: None,
+            })
+        };
+
+        assert_eq!(submit(&mut contract, accounts(3), 100), InvestigationStatus::Processing);
+        assert_eq!(submit(&mut contract, accounts(4), 105), InvestigationStatus::Completed);
+        assert_eq!(contract.get_oracle_submissions(token_id.clone()).len(), 2);
+        assert_eq!(contract.get_investigation_status(token_id), Some(InvestigationStatus::Completed));
+    }
+
+    #[test]
+    fn test_oracle_stake_slash_and_unbond() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+        let mut contract = Contract::new(accounts(1), None);
+
+        testing_env!(get_context(accounts(3)).attached_deposit(DEFAULT_MIN_ORACLE_STAKE).build());
+        contract.register_oracle();
+        assert_eq!(contract.get_oracles(), vec![accounts(3)]);
+
+        testing_env!(get_context(accounts(1)).build());
+        let stake = contract.slash_oracle(accounts(3), NearToken::from_near(1), "Lost dispute".to_string());
+        assert_eq!(stake.staked, NearToken::from_near(9));
+        assert!(contract.get_oracles().is_empty());
+
+        testing_env!(get_context(accounts(3)).build());
+        let stake = contract.unstake(NearToken::from_near(9));
+        assert_eq!(stake.unbonding, NearToken::from_near(9));
+
+        testing_env!(get_context(accounts(3))
+            .block_timestamp(stake.unbonding_ends_at.unwrap().0)
+            .build());
+        contract.withdraw_stake();
+        assert!(contract.get_oracle_stake(accounts(3)).is_none());
+    }
+
+    #[test]
+    fn test_dispute_rejected_restores_status() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let token_id = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        let mut metadata = InvestigationMetadata::new(1, accounts(2), accounts(1));
+        metadata.status = InvestigationStatus::Completed;
+        metadata.is_bot = true;
+        let _ = contract.update_investigation_metadata(
+            token_id.clone(),
+            MetadataUpdate {
+                description: None,
+                extra: serde_json::to_string(&metadata).unwrap(),
+                reference: None,
+                reference_hash: None,
+            },
+            WebhookType::Completion,
+            None,
+        );
+
+        testing_env!(get_context(accounts(2)).attached_deposit(DEFAULT_DISPUTE_BOND).build());
+        contract.open_dispute(token_id.clone(), "Not a bot".to_string(), None);
+        assert_eq!(contract.get_investigation_status(token_id.clone()), Some(InvestigationStatus::Disputed));
+
+        testing_env!(get_context(accounts(1)).build());
+        let resolved = contract.resolve_dispute(token_id.clone(), DisputeOutcome::Rejected, "Evidence holds".to_string());
+        assert_eq!(resolved.resolution.unwrap().outcome, DisputeOutcome::Rejected);
+        assert_eq!(contract.get_investigation_status(token_id.clone()), Some(InvestigationStatus::Completed));
+        assert!(contract.get_open_dispute(token_id.clone()).is_none());
+        assert_eq!(contract.get_disputes(token_id).len(), 1);
+
+        assert_eq!(contract.get_forfeited_bonds(), DEFAULT_DISPUTE_BOND);
+        contract.withdraw_forfeited_bonds(None);
+        assert!(contract.get_forfeited_bonds().is_zero());
+    }
+
+    #[test]
+    fn test_redact_investigation_blanks_fields() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let token_id = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        let mut metadata = InvestigationMetadata::new(1, accounts(2), accounts(1));
+        metadata.summary = Some("Sensitive findings".to_string());
+        metadata.near_balance = "42".to_string();
+        let update = MetadataUpdate {
+            description: None,
+            extra: serde_json::to_string(&metadata).unwrap(),
+            reference: None,
+            reference_hash: None,
+        };
+        let _ = contract.update_investigation_metadata(token_id.clone(), update, WebhookType::Progress, None);
+
+        contract.redact_investigation(
+            token_id.clone(),
+            vec![RedactableField::Summary, RedactableField::NearBalance],
+            "Privacy request".to_string(),
+        );
+        let redacted = contract.get_investigation_metadata(token_id.clone()).unwrap();
+        assert!(redacted.redacted);
+        assert_eq!(redacted.summary, None);
+        assert_eq!(redacted.near_balance, "[redacted]");
+        assert_eq!(contract.get_redactions(token_id).len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Target account has opted out of investigations")]
+    fn test_opted_out_target_blocked() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        testing_env!(get_context(accounts(2)).build());
+        contract.add_opt_out(accounts(2));
+        assert!(contract.is_opted_out(accounts(2)));
+
+        testing_env!(get_context(accounts(3)).build());
+        let _ = contract.start_investigation(accounts(2), None);
+    }
+
+    #[test]
+    fn test_private_investigation_keeps_only_ciphertext() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let options = InvestigationOptions {
+            visibility: Visibility::Private,
+            encryption_public_key: Some(vec![9u8; 32].into()),
+            ..Default::default()
+        };
+        let token_id = contract.start_investigation(accounts(2), Some(options)).unwrap().request_id;
+
+        let mut metadata = InvestigationMetadata::new(1, accounts(2), accounts(1));
+        metadata.encrypted_payload = Some(vec![1u8; 48].into());
+        metadata.transaction_count = 7;
+        metadata.is_bot = true;
+        metadata.risk_level = RiskLevel::High;
+        let update = MetadataUpdate {
+            description: None,
+            extra: serde_json::to_string(&metadata).unwrap(),
+            reference: None,
+            reference_hash: None,
+        };
+        let _ = contract.update_investigation_metadata(token_id.clone(), update, WebhookType::Progress, None);
+
+        let stored = contract.get_investigation_metadata(token_id.clone()).unwrap();
+        assert_eq!(stored.summary, None);
+        assert_eq!(stored.transaction_count, 0);
+        assert!(!stored.is_bot);
+        assert_eq!(stored.risk_level, RiskLevel::Unknown);
+        assert_eq!(stored.visibility, Visibility::Private);
+        assert!(stored.encrypted_payload.is_some());
+        let card = contract.get_token_metadata(token_id.clone()).unwrap();
+        assert_eq!(card.media_hash.unwrap().0, near_sdk::env::sha256(render_case_svg(&stored, true).as_bytes()));
+        assert_eq!(contract.get_public_investigation(token_id).unwrap().visibility, Visibility::Private);
+    }
+
+    #[test]
+    fn test_archive_expired_investigation() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let options = InvestigationOptions {
+            retention_period_ns: Some(U64(1_000)),
+            ..Default::default()
+        };
+        let token_id = contract.start_investigation(accounts(2), Some(options)).unwrap().request_id;
+
+        testing_env!(get_context(accounts(1)).block_timestamp(1_000).build());
+        let refund = contract.archive_investigation(token_id.clone());
+        assert!(!refund.is_zero());
+        assert!(contract.nft_token(token_id.clone()).is_none());
+        assert!(contract.get_investigation_status(token_id).is_none());
+        assert!(contract.get_investigation_by_account(accounts(2)).is_none());
+    }
+
+    #[test]
+    fn test_expire_stale_investigation_and_retry() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let token_id = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        assert!(contract.get_stale_investigations(None, None).is_empty());
+
+        testing_env!(get_context(accounts(3))
+            .block_timestamp(DEFAULT_INVESTIGATION_TIMEOUT_NS)
+            .build());
+        assert_eq!(contract.get_stale_investigations(None, None), vec![token_id.clone()]);
+        assert_eq!(contract.expire_stale_investigation(token_id.clone()), InvestigationStatus::Failed);
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(DEFAULT_INVESTIGATION_TIMEOUT_NS)
+            .build());
+        assert!(contract.retry_investigation(token_id.clone()).is_ok());
+        assert_eq!(contract.get_investigation_status(token_id), Some(InvestigationStatus::Pending));
+    }
+
+    #[test]
+    fn test_job_queue_claim_and_lease_expiry() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let first = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        let second = contract.start_investigation(accounts(3), None).unwrap().request_id;
+        assert_eq!(contract.get_job_queue(None, None), vec![first.clone(), second.clone()]);
+
+        let job = contract.claim_next_job("worker-1".to_string()).unwrap();
+        assert_eq!(job.token_id, first);
+        assert_eq!(contract.get_investigation_status(first.clone()), Some(InvestigationStatus::Processing));
+        assert_eq!(contract.get_job_queue_length(), 1);
+
+        // An expired lease returns the case to the front of the queue
+        testing_env!(get_context(accounts(1)).block_timestamp(DEFAULT_JOB_LEASE_NS).build());
+        let job = contract.claim_next_job("worker-2".to_string()).unwrap();
+        assert_eq!(job.token_id, first);
+        assert_eq!(contract.get_job_lease(first).unwrap().worker_id, "worker-2");
+    }
+
+    #[test]
+    fn test_priority_tier_claimed_first() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let standard = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        testing_env!(get_context(accounts(1)).attached_deposit(NearToken::from_millinear(100).saturating_add(MINT_STORAGE_COST)).build());
+        let options = InvestigationOptions {
+            tier: InvestigationTier::Priority,
+            ..Default::default()
+        };
+        let priority = contract.start_investigation(accounts(3), Some(options)).unwrap().request_id;
+
+        assert_eq!(contract.get_case_tier(priority.clone()), InvestigationTier::Priority);
+        assert_eq!(contract.get_job_queue(None, None), vec![priority.clone(), standard]);
+        let job = contract.claim_next_job("worker-1".to_string()).unwrap();
+        assert_eq!(job.token_id, priority);
+        assert_eq!(job.tier, InvestigationTier::Priority);
+    }
+
+    #[test]
+    fn test_start_investigations_batch() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let existing = contract.start_investigation(accounts(2), None).unwrap().request_id;
+
+        let responses = contract.start_investigations(
+            vec![accounts(2), accounts(3), accounts(4), accounts(3)],
+            None,
+        );
+        assert_eq!(responses.len(), 4);
+        assert_eq!(responses[0].request_id, existing);
+        assert!(responses[0].message.is_some());
+        assert!(responses[1].message.is_none());
+        assert!(responses[2].message.is_none());
+        assert!(responses[3].message.is_some());
+        assert_eq!(contract.get_job_queue_length(), 3);
+    }
+
+    #[test]
+    fn test_watchlist_subscriptions() {
+        let context = get_context(accounts(3));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        contract.add_to_watchlist(accounts(2));
+        assert_eq!(contract.get_watchlist(accounts(3)), vec![accounts(2)]);
+        assert_eq!(contract.get_watchers(accounts(2)), vec![accounts(3)]);
+
+        let token_id = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        testing_env!(get_context(accounts(1)).build());
+        contract.claim_next_job("worker-1".to_string());
+        assert!(near_sdk::test_utils::get_logs()
+            .iter()
+            .any(|log| log.contains("WatchlistNotification") && log.contains(&token_id)));
+
+        testing_env!(get_context(accounts(3)).build());
+
+        contract.remove_from_watchlist(accounts(2));
+        assert!(contract.get_watchlist(accounts(3)).is_empty());
+        assert!(contract.get_watchers(accounts(2)).is_empty());
+    }
+
+    #[test]
+    fn test_monitoring_subscription() {
+        let mut context = get_context(accounts(3));
+        testing_env!(context.build());
+        let mut contract = Contract::new(accounts(1), None);
+        context.attached_deposit(contract.get_monitoring_fee().saturating_mul(2));
+        testing_env!(context.build());
+
+        let subscription = contract.subscribe_monitoring(accounts(2), U64(MIN_MONITORING_INTERVAL_NS), 2);
+        assert!(contract.tick_monitoring(None).is_empty());
+
+        context.block_timestamp(MIN_MONITORING_INTERVAL_NS);
+        testing_env!(context.build());
+        assert_eq!(contract.tick_monitoring(None), vec![U64(subscription.id)]);
+        let token_id = contract.investigated_accounts.get(&accounts(2)).unwrap();
+        assert_eq!(contract.investigation_status.get(&token_id), Some(InvestigationStatus::Pending));
+        assert_eq!(contract.get_monitoring_subscription(U64(subscription.id)).unwrap().periods_remaining, 1);
+
+        // Half of the current period has elapsed, so only half of its fee comes back
+        context.block_timestamp(MIN_MONITORING_INTERVAL_NS + MIN_MONITORING_INTERVAL_NS / 2);
+        testing_env!(context.build());
+        let refund = contract.cancel_monitoring(U64(subscription.id));
+        assert_eq!(refund.as_yoctonear(), subscription.fee_per_period.as_yoctonear() / 2);
+        assert!(contract.get_monitoring_subscriptions_for(accounts(3)).is_empty());
+    }
+
+    #[test]
+    fn test_bounty_paid_to_resolver() {
+        let mut context = get_context(accounts(3));
+        context.attached_deposit(NearToken::from_near(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let bounty = contract.create_bounty(accounts(2), NearToken::from_near(1), U64(1_000));
+        testing_env!(get_context(accounts(4)).attached_deposit(NearToken::from_near(2)).build());
+        assert_eq!(contract.contribute_to_bounty(U64(bounty.id)), NearToken::from_near(3));
+        assert_eq!(contract.get_open_bounties(None, None).len(), 1);
+
+        let token_id = complete_bounty_target(&mut contract);
+
+        // Neither the dispute window nor an open dispute may be skipped
+        testing_env!(get_context(accounts(2)).attached_deposit(DEFAULT_DISPUTE_BOND).build());
+        contract.open_dispute(token_id.clone(), "Wrong result".to_string(), None);
+        testing_env!(get_context(accounts(1)).build());
+        contract.resolve_dispute(token_id, DisputeOutcome::Rejected, "Result stands".to_string());
+
+        testing_env!(get_context(accounts(1)).block_timestamp(BOUNTY_DISPUTE_WINDOW_NS).build());
+        assert_eq!(contract.claim_bounty(U64(bounty.id)), vec![accounts(5)]);
+        assert_eq!(contract.get_bounty(U64(bounty.id)).unwrap().status, BountyStatus::Paid);
+        assert!(contract.get_open_bounties(None, None).is_empty());
+    }
+
+    /// Completes the case on `accounts(2)` with staked oracle `accounts(5)` as the resolver
+    fn complete_bounty_target(contract: &mut Contract) -> TokenId {
+        testing_env!(get_context(accounts(1)).build());
+        let token_id = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        testing_env!(get_context(accounts(5)).attached_deposit(DEFAULT_MIN_ORACLE_STAKE).build());
+        contract.register_oracle();
+        testing_env!(get_context(accounts(5)).build());
+        let mut metadata = InvestigationMetadata::new(1, accounts(2), accounts(1));
+        metadata.status = InvestigationStatus::Completed;
+        let _ = contract.update_investigation_metadata(
+            token_id.clone(),
+            MetadataUpdate {
+                description: None,
+                extra: serde_json::to_string(&metadata).unwrap(),
+                reference: None,
+                reference_hash: None,
+            },
+            WebhookType::Completion,
+            None,
+        );
+        token_id
+    }
+
+    #[test]
+    #[should_panic(expected = "The dispute window on the result has not passed yet")]
+    fn test_bounty_claim_waits_for_dispute_window() {
+        let mut context = get_context(accounts(3));
+        context.attached_deposit(NearToken::from_near(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let bounty = contract.create_bounty(accounts(2), NearToken::from_near(1), U64(1_000));
+        complete_bounty_target(&mut contract);
+        contract.claim_bounty(U64(bounty.id));
+    }
+
+    #[test]
+    #[should_panic(expected = "Bounty has a valid resolution and must be claimed")]
+    fn test_bounty_refund_blocked_by_resolution() {
+        let mut context = get_context(accounts(3));
+        context.attached_deposit(NearToken::from_near(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let bounty = contract.create_bounty(accounts(2), NearToken::from_near(1), U64(1_000));
+        complete_bounty_target(&mut contract);
+
+        testing_env!(get_context(accounts(3)).block_timestamp(1_000).build());
+        contract.refund_bounty(U64(bounty.id));
+    }
+
+    #[test]
+    fn test_analyst_marketplace_escrow() {
+        let context = get_context(accounts(4));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        contract.register_analyst("Sleuth".to_string(), "On-chain forensics".to_string(), NearToken::from_millinear(50));
+
+        testing_env!(get_context(accounts(3)).attached_deposit(NearToken::from_millinear(60).saturating_add(MINT_STORAGE_COST)).build());
+        let options = InvestigationOptions {
+            analyst: Some(accounts(4)),
+            ..Default::default()
+        };
+        let token_id = contract.start_investigation(accounts(2), Some(options)).unwrap().request_id;
+        assert!(contract.get_job_queue(None, None).is_empty());
+
+        testing_env!(get_context(accounts(4)).build());
+        let mut metadata = InvestigationMetadata::new(1, accounts(2), accounts(3));
+        metadata.status = InvestigationStatus::Completed;
+        let update = MetadataUpdate {
+            description: None,
+            extra: serde_json::to_string(&metadata).unwrap(),
+            reference: None,
+            reference_hash: None,
+        };
+        assert!(contract.update_investigation_metadata(token_id.clone(), update, WebhookType::Completion, None).is_ok());
+        assert_eq!(contract.get_analyst_assignment(token_id.clone()).unwrap().status, AssignmentStatus::Submitted);
+        assert_eq!(contract.get_investigation_status(token_id.clone()), Some(InvestigationStatus::UnderReview));
+
+        testing_env!(get_context(accounts(3)).build());
+        let assignment = contract.reject_analyst_result(token_id.clone());
+        assert_eq!(assignment.status, AssignmentStatus::Rejected);
+        assert_eq!(contract.get_investigation_status(token_id.clone()), Some(InvestigationStatus::Pending));
+        assert_eq!(contract.get_job_queue(None, None), vec![token_id]);
+    }
+
+    #[test]
+    fn test_reputation_tracks_completions_and_disputes() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let token_id = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        let mut metadata = InvestigationMetadata::new(1, accounts(2), accounts(1));
+        metadata.status = InvestigationStatus::Completed;
+        testing_env!(get_context(accounts(1)).block_timestamp(500).build());
+        let _ = contract.update_investigation_metadata(
+            token_id.clone(),
+            MetadataUpdate {
+                description: None,
+                extra: serde_json::to_string(&metadata).unwrap(),
+                reference: None,
+                reference_hash: None,
+            },
+            WebhookType::Completion,
+            None,
+        );
+
+        let reputation = contract.get_reputation(accounts(1));
+        assert_eq!(reputation.completed_cases, 1);
+        assert_eq!(reputation.average_turnaround_ns, U64(500));
+        assert_eq!(reputation.score, 10);
+
+        testing_env!(get_context(accounts(2)).attached_deposit(DEFAULT_DISPUTE_BOND).build());
+        contract.open_dispute(token_id.clone(), "Wrong result".to_string(), None);
+        testing_env!(get_context(accounts(1)).build());
+        contract.resolve_dispute(token_id, DisputeOutcome::Upheld, "Re-run".to_string());
+
+        let leaderboard = contract.get_leaderboard(None, None);
+        assert_eq!(leaderboard.len(), 1);
+        assert_eq!(leaderboard[0].disputes_lost, 1);
+        assert_eq!(leaderboard[0].score, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Rating can no longer be edited")]
+    fn test_rating_edit_window() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        testing_env!(get_context(accounts(3)).build());
+        let token_id = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        testing_env!(context.build());
+        let mut metadata = InvestigationMetadata::new(1, accounts(2), accounts(3));
+        metadata.status = InvestigationStatus::Completed;
+        let _ = contract.update_investigation_metadata(
+            token_id.clone(),
+            MetadataUpdate {
+                description: None,
+                extra: serde_json::to_string(&metadata).unwrap(),
+                reference: None,
+                reference_hash: None,
+            },
+            WebhookType::Completion,
+            None,
+        );
+
+        testing_env!(get_context(accounts(3)).build());
+        contract.rate_investigation(token_id.clone(), 5, Some("Thorough".to_string()));
+        contract.rate_investigation(token_id.clone(), 4, None);
+        let stats = contract.get_rating_stats();
+        assert_eq!(stats.rating_count, 1);
+        assert_eq!(stats.rating_total, U64(4));
+        assert_eq!(contract.get_reputation(accounts(1)).rating_total, 4);
+
+        testing_env!(get_context(accounts(3)).block_timestamp(RATING_EDIT_WINDOW_NS).build());
+        contract.rate_investigation(token_id, 1, None);
+    }
+
+    #[test]
+    fn test_collaborative_case_notes() {
+        let context = get_context(accounts(3));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let token_id = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        contract.add_collaborator(token_id.clone(), accounts(4));
+        contract.add_case_note(token_id.clone(), "Check the bridge deposits".to_string());
+
+        testing_env!(get_context(accounts(4)).build());
+        assert_eq!(contract.add_case_note(token_id.clone(), "Likely a wash trader".to_string()), 2);
+        let notes = contract.get_case_notes(token_id.clone(), None, None);
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[0].visibility, NoteVisibility::Public);
+        let page = contract.get_case_notes(token_id.clone(), Some(1), Some(5));
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].author, accounts(4));
+
+        testing_env!(get_context(accounts(3)).build());
+        contract.remove_collaborator(token_id.clone(), accounts(4));
+        assert!(contract.get_collaborators(token_id.clone()).is_empty());
+        assert_eq!(contract.get_case_notes(token_id, None, None).len(), 2);
+    }
+
+    #[test]
+    fn test_merge_duplicate_case() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let primary = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        let duplicate = contract.start_investigation(accounts(3), None).unwrap().request_id;
+        contract.add_case_note(duplicate.clone(), "Same controller as the main account".to_string());
+        contract.link_cases(primary.clone(), duplicate.clone(), CaseRelation::SameController);
+
+        contract.merge_cases(primary.clone(), duplicate.clone());
+        assert_eq!(contract.get_investigation_status(duplicate.clone()), Some(InvestigationStatus::Merged));
+        assert_eq!(contract.investigated_accounts.get(&accounts(3)), Some(primary.clone()));
+        assert_eq!(contract.get_case_notes(primary.clone(), None, None).len(), 1);
+        assert_eq!(contract.get_case_note_count(duplicate.clone()), 0);
+        assert_eq!(contract.get_merged_cases(primary.clone()), vec![duplicate.clone()]);
+        assert_eq!(contract.get_case_links(primary).len(), 2);
+        assert!(!contract.get_job_queue(None, None).contains(&duplicate));
+    }
+
+    #[test]
+    fn test_sub_account_hierarchy_and_rolldown() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let parent: AccountId = "bar.near".parse().unwrap();
+        let child: AccountId = "foo.bar.near".parse().unwrap();
+        let mut contract = Contract::new(accounts(1), None);
+        contract.set_label_rolldown(true);
+
+        let parent_token = contract.start_investigation(parent.clone(), None).unwrap().request_id;
+        let mut metadata = InvestigationMetadata::new(1, parent.clone(), accounts(1));
+        metadata.status = InvestigationStatus::Completed;
+        metadata.risk_level = RiskLevel::High;
+        let _ = contract.update_investigation_metadata(
+            parent_token,
+            MetadataUpdate {
+                description: None,
+                extra: serde_json::to_string(&metadata).unwrap(),
+                reference: None,
+                reference_hash: None,
+            },
+            WebhookType::Completion,
+            None,
+        );
+
+        let child_token = contract.start_investigation(child.clone(), None).unwrap().request_id;
+        let child_case = contract.get_investigation_metadata(child_token.clone()).unwrap();
+        assert_eq!(child_case.account_kind, AccountKind::Named);
+        assert_eq!(child_case.parent_accounts, vec![parent.clone()]);
+        assert_eq!(child_case.risk_level, RiskLevel::High);
+        assert_eq!(child_case.risk_inherited_from, Some(parent.clone()));
+        assert_eq!(contract.get_cases_under_parent(parent, None, None), vec![child_token]);
+
+        let implicit: AccountId = "a".repeat(64).parse().unwrap();
+        assert_eq!(AccountKind::of(&implicit), AccountKind::Implicit);
+        assert!(parent_accounts(&implicit).is_empty());
+    }
+
+    #[test]
+    fn test_case_artwork_tracks_status() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let token_id = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        let minted = contract.get_token_metadata(token_id.clone()).unwrap();
+        let media = minted.media.unwrap();
+        assert!(media.starts_with("data:image/svg+xml;base64,"));
+
+        let investigation = contract.get_investigation_metadata(token_id.clone()).unwrap();
+        let svg = render_case_svg(&investigation, false);
+        assert!(svg.contains("CASE FILE #1") && svg.contains("Pending") && svg.contains("1970-01-01 00:00 UTC"));
+        assert_eq!(minted.media_hash.unwrap().0, near_sdk::env::sha256(svg.as_bytes()));
+
+        testing_env!(get_context(accounts(1)).block_timestamp(1_700_000_000_000_000_000).build());
+        contract.claim_next_job("worker-1".to_string());
+        let refreshed = contract.get_token_metadata(token_id.clone()).unwrap();
+        assert_ne!(refreshed.media.unwrap(), media);
+        let mut investigation = contract.get_investigation_metadata(token_id).unwrap();
+        investigation.last_updated = U64(1_700_000_000_000_000_000);
+        assert!(render_case_svg(&investigation, false).contains("2023-11-14 22:13 UTC"));
+    }
+
+    #[test]
+    fn test_contract_metadata_defaults_and_update() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        assert!(near_sdk::test_utils::get_logs().iter().any(|log| log.contains("ContractInitialized")));
+        let metadata = contract.nft_metadata();
+        assert_eq!(metadata.spec, NFT_METADATA_SPEC);
+        assert_eq!(metadata.icon, Some(DEFAULT_ICON_URL.to_string()));
+
+        contract.set_contract_metadata(NFTContractMetadata {
+            symbol: "CASE".to_string(),
+            ..metadata
+        });
+        assert_eq!(contract.nft_metadata().symbol, "CASE");
+    }
+
+    #[test]
+    #[should_panic(expected = "Spec is not NFT metadata")]
+    fn test_new_rejects_invalid_metadata() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        Contract::new(accounts(1), Some(NFTContractMetadata {
+            spec: "nft-0.0.1".to_string(),
+            ..default_contract_metadata()
+        }));
+    }
+
+    #[test]
+    fn test_new() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+
+        let contract = Contract::new(accounts(0), None);
+        assert_eq!(contract.owner_id, accounts(0));
+        assert_eq!(contract.case_number_counter, 0);
+    }
+
+    #[test]
+    fn test_start_investigations_requeues_stale_cases() {
+        testing_env!(get_context(accounts(1)).block_timestamp(1_000).build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let stale = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        contract.claim_next_job("worker-1".to_string());
+        assert_eq!(contract.get_job_queue_length(), 0);
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(1_000 + DEFAULT_INVESTIGATION_TIMEOUT_NS)
+            .build());
+        let responses = contract.start_investigations(vec![accounts(2)], None);
+        assert_eq!(responses[0].request_id, stale);
+        assert_eq!(responses[0].status, InvestigationStatus::Pending);
+        assert_eq!(contract.investigation_status.get(&stale), Some(InvestigationStatus::Pending));
+        assert!(!contract.is_stale(&stale));
+        assert_eq!(contract.get_job_queue_length(), 1);
+    }
+
+    #[test]
+    fn test_stale_uses_tier_sla() {
+        testing_env!(get_context(accounts(1))
+            .attached_deposit(NearToken::from_millinear(100).saturating_add(MINT_STORAGE_COST))
+            .block_timestamp(1_000)
+            .build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let options = InvestigationOptions {
+            tier: InvestigationTier::Priority,
+            ..Default::default()
+        };
+        let token_id = contract.start_investigation(accounts(2), Some(options)).unwrap().request_id;
+        let sla = contract.tier_config(&InvestigationTier::Priority).sla_ns.0;
+
+        testing_env!(get_context(accounts(1)).block_timestamp(1_000 + sla - 1).build());
+        assert!(!contract.is_stale(&token_id));
+        testing_env!(get_context(accounts(1)).block_timestamp(1_000 + sla).build());
+        assert!(contract.is_stale(&token_id));
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot archive a case with an open dispute")]
+    fn test_archive_blocked_by_open_dispute() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let options = InvestigationOptions {
+            retention_period_ns: Some(U64(1_000)),
+            ..Default::default()
+        };
+        let token_id = contract.start_investigation(accounts(2), Some(options)).unwrap().request_id;
+        let mut metadata = InvestigationMetadata::new(1, accounts(2), accounts(1));
+        metadata.status = InvestigationStatus::Completed;
+        let _ = contract.update_investigation_metadata(
+            token_id.clone(),
+            MetadataUpdate {
+                description: None,
+                extra: serde_json::to_string(&metadata).unwrap(),
+                reference: None,
+                reference_hash: None,
+            },
+            WebhookType::Completion,
+            None,
+        );
+
+        testing_env!(get_context(accounts(2)).attached_deposit(DEFAULT_DISPUTE_BOND).build());
+        contract.open_dispute(token_id.clone(), "Not a bot".to_string(), None);
+
+        testing_env!(get_context(accounts(2)).block_timestamp(1_000).build());
+        contract.archive_investigation(token_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Private investigations cannot carry a plaintext summary or financials")]
+    fn test_private_investigation_rejects_plaintext() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let options = InvestigationOptions {
+            visibility: Visibility::Private,
+            encryption_public_key: Some(vec![9u8; 32].into()),
+            ..Default::default()
+        };
+        let token_id = contract.start_investigation(accounts(2), Some(options)).unwrap().request_id;
+
+        let mut metadata = InvestigationMetadata::new(1, accounts(2), accounts(1));
+        metadata.summary = Some("Plaintext leak".to_string());
+        metadata.encrypted_payload = Some(vec![1u8; 48].into());
+        let update = MetadataUpdate {
+            description: None,
+            extra: serde_json::to_string(&metadata).unwrap(),
+            reference: None,
+            reference_hash: None,
+        };
+        let _ = contract.update_investigation_metadata(token_id, update, WebhookType::Progress, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Oracle has been removed")]
+    fn test_removed_oracle_cannot_reregister() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+        let mut contract = Contract::new(accounts(1), None);
+
+        testing_env!(get_context(accounts(3)).attached_deposit(DEFAULT_MIN_ORACLE_STAKE).build());
+        contract.register_oracle();
+        testing_env!(get_context(accounts(1)).build());
+        contract.remove_oracle(accounts(3));
+
+        testing_env!(get_context(accounts(3)).attached_deposit(DEFAULT_MIN_ORACLE_STAKE).build());
+        contract.register_oracle();
+    }
+
+    #[test]
+    fn test_upheld_dispute_slashes_resolver() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+        let mut contract = Contract::new(accounts(1), None);
+        let token_id = contract.start_investigation(accounts(2), None).unwrap().request_id;
+
+        testing_env!(get_context(accounts(3)).attached_deposit(DEFAULT_MIN_ORACLE_STAKE).build());
+        contract.register_oracle();
+        testing_env!(get_context(accounts(3)).build());
+        let mut metadata = InvestigationMetadata::new(1, accounts(2), accounts(1));
+        metadata.status = InvestigationStatus::Completed;
+        let _ = contract.update_investigation_metadata(
+            token_id.clone(),
+            MetadataUpdate {
+                description: None,
+                extra: serde_json::to_string(&metadata).unwrap(),
+                reference: None,
+                reference_hash: None,
+            },
+            WebhookType::Completion,
+            None,
+        );
+
+        testing_env!(get_context(accounts(2)).attached_deposit(DEFAULT_DISPUTE_BOND).build());
+        contract.open_dispute(token_id.clone(), "Wrong result".to_string(), None);
+        testing_env!(get_context(accounts(1)).build());
+        contract.resolve_dispute(token_id, DisputeOutcome::Upheld, "Result was wrong".to_string());
+
+        let stake = contract.get_oracle_stake(accounts(3)).unwrap();
+        assert_eq!(stake.total_slashed, DEFAULT_DISPUTE_SLASH);
+        assert_eq!(contract.get_slashed_balance(), DEFAULT_DISPUTE_SLASH);
+
+        contract.withdraw_slashed_balance(None);
+        assert!(contract.get_slashed_balance().is_zero());
+    }
+
+    #[test]
+    fn test_redaction_covers_oracle_submissions() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let token_id = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        for oracle in [accounts(3), accounts(4)] {
+            testing_env!(get_context(oracle).attached_deposit(DEFAULT_MIN_ORACLE_STAKE).build());
+            contract.register_oracle();
+        }
+        testing_env!(get_context(accounts(1)).build());
+        contract.set_consensus_config(ConsensusConfig {
+            enabled: true,
+            quorum: 2,
+            transaction_count_tolerance_bps: 0,
+        });
+
+        testing_env!(get_context(accounts(3)).build());
+        let mut metadata = InvestigationMetadata::new(1, accounts(2), accounts(1));
+        metadata.summary = Some("Sensitive findings".to_string());
+        contract.submit_result(token_id.clone(), MetadataUpdate {
+            description: None,
+            extra: serde_json::to_string(&metadata).unwrap(),
+            reference: None,
+            reference_hash: None,
+        });
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.redact_investigation(token_id.clone(), vec![RedactableField::Summary], "Privacy request".to_string());
+        let submissions = contract.get_oracle_submissions(token_id);
+        assert_eq!(submissions[0].metadata.summary, None);
+        assert!(submissions[0].metadata.redacted);
+    }
+
+    #[test]
+    fn test_monitoring_stops_when_target_opts_out() {
+        let mut context = get_context(accounts(3));
+        testing_env!(context.build());
+        let mut contract = Contract::new(accounts(1), None);
+        context.attached_deposit(contract.get_monitoring_fee().saturating_mul(2));
+        testing_env!(context.build());
+
+        let subscription = contract.subscribe_monitoring(accounts(2), U64(MIN_MONITORING_INTERVAL_NS), 2);
+        testing_env!(get_context(accounts(2)).build());
+        contract.add_opt_out(accounts(2));
+
+        context.block_timestamp(MIN_MONITORING_INTERVAL_NS);
+        testing_env!(context.build());
+        assert!(contract.tick_monitoring(None).is_empty());
+        assert!(contract.get_monitoring_subscription(U64(subscription.id)).is_none());
+        assert!(contract.investigated_accounts.get(&accounts(2)).is_none());
+        assert!(near_sdk::test_utils::get_logs()
+            .iter()
+            .any(|log| log.contains("MonitoringCancelled") && log.contains(&subscription.fee_per_period.saturating_mul(2).as_yoctonear().to_string())));
+    }
+
+    #[test]
+    fn test_heartbeat_keeps_lease_past_original_expiry() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let first = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        let second = contract.start_investigation(accounts(3), None).unwrap().request_id;
+        contract.claim_next_job("worker-1".to_string());
+
+        testing_env!(get_context(accounts(1)).block_timestamp(DEFAULT_JOB_LEASE_NS / 2).build());
+        contract.heartbeat(first.clone());
+
+        // The original lease has run out, but the heartbeat moved it to a later slot
+        testing_env!(get_context(accounts(1)).block_timestamp(DEFAULT_JOB_LEASE_NS).build());
+        let job = contract.claim_next_job("worker-2".to_string()).unwrap();
+        assert_eq!(job.token_id, second);
+        assert_eq!(contract.get_job_lease(first).unwrap().worker_id, "worker-1");
+        assert_eq!(contract.leases_by_expiry.len(), 2);
+    }
+
+    #[test]
+    fn test_retry_records_price_and_payer() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let token_id = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        testing_env!(get_context(accounts(3)).block_timestamp(DEFAULT_INVESTIGATION_TIMEOUT_NS).build());
+        contract.expire_stale_investigation(token_id.clone());
+
+        testing_env!(get_context(accounts(1))
+            .attached_deposit(NearToken::from_near(1))
+            .block_timestamp(DEFAULT_INVESTIGATION_TIMEOUT_NS)
+            .build());
+        assert!(contract.retry_investigation(token_id.clone()).is_ok());
+        assert_eq!(contract.case_payments.get(&token_id), Some(INVESTIGATION_FEE));
+        assert_eq!(contract.case_payers.get(&token_id), Some(accounts(1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the requester or an oracle can retry an investigation")]
+    fn test_retry_restricted_to_requester_or_oracle() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let token_id = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        testing_env!(get_context(accounts(3)).block_timestamp(DEFAULT_INVESTIGATION_TIMEOUT_NS).build());
+        contract.expire_stale_investigation(token_id.clone());
+        let _ = contract.retry_investigation(token_id);
+    }
+
+    #[test]
+    fn test_analyst_claims_escrow_after_review_period() {
+        let context = get_context(accounts(4));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        contract.register_analyst("Sleuth".to_string(), "On-chain forensics".to_string(), NearToken::from_millinear(50));
+
+        testing_env!(get_context(accounts(3)).attached_deposit(NearToken::from_millinear(60).saturating_add(MINT_STORAGE_COST)).build());
+        let options = InvestigationOptions {
+            analyst: Some(accounts(4)),
+            ..Default::default()
+        };
+        let token_id = contract.start_investigation(accounts(2), Some(options)).unwrap().request_id;
+
+        testing_env!(get_context(accounts(4)).build());
+        let mut metadata = InvestigationMetadata::new(1, accounts(2), accounts(3));
+        metadata.status = InvestigationStatus::Completed;
+        let update = MetadataUpdate {
+            description: None,
+            extra: serde_json::to_string(&metadata).unwrap(),
+            reference: None,
+            reference_hash: None,
+        };
+        assert!(contract.update_investigation_metadata(token_id.clone(), update, WebhookType::Completion, None).is_ok());
+
+        testing_env!(get_context(accounts(4)).block_timestamp(ANALYST_REVIEW_PERIOD_NS).build());
+        let assignment = contract.claim_analyst_escrow(token_id.clone());
+        assert_eq!(assignment.status, AssignmentStatus::Accepted);
+        assert_eq!(contract.get_investigation_status(token_id.clone()), Some(InvestigationStatus::Completed));
+        assert_eq!(contract.get_case_resolution(token_id).unwrap().resolvers, vec![accounts(4)]);
+    }
+
+    #[test]
+    fn test_merge_settles_unfinished_duplicate() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let primary = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        let duplicate = contract.start_investigation(accounts(3), None).unwrap().request_id;
+        contract.add_evidence(duplicate.clone(), EvidenceKind::Other, "ipfs://bafy-x".to_string(), "c".repeat(64), None, None);
+        testing_env!(get_context(accounts(4)).attached_deposit(NearToken::from_near(1)).build());
+        let bounty = contract.create_bounty(accounts(3), NearToken::from_near(1), U64(1_000));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.merge_cases(primary.clone(), duplicate.clone());
+        assert!(contract.case_payments.get(&duplicate).is_none());
+        assert_eq!(contract.get_bounty(U64(bounty.id)).unwrap().status, BountyStatus::Refunded);
+        assert_eq!(
+            contract.get_evidence_manifest_record(primary.clone()).unwrap().hash,
+            contract.evidence_manifest_hash(&primary)
+        );
+        assert!(contract.get_evidence_manifest_record(duplicate).is_none());
+    }
+
+    #[test]
+    fn test_migrate_from_version_one() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let old_state = OldState {
+            version: 1,
+            owner_id: accounts(1),
+            tokens: NonFungibleToken::new(
+                StorageKey::NonFungibleToken,
+                accounts(1),
+                Some(StorageKey::TokenMetadata),
+                Some(StorageKey::Enumeration),
+                Some(StorageKey::Approval),
+            ),
+            metadata: LazyOption::new(StorageKey::Metadata, Some(&default_contract_metadata())),
+            case_number_counter: 7,
+            investigated_accounts: LookupMap::new(StorageKey::InvestigatedAccounts),
+            investigation_status: UnorderedMap::new(StorageKey::InvestigationStatus),
+            investigation_data: UnorderedMap::new(StorageKey::InvestigationData),
+            failed_mints: UnorderedMap::new(StorageKey::FailedMints),
+        };
+        env::state_write(&old_state);
+
+        let contract = Contract::migrate();
+        assert_eq!(contract.version, 2);
+        assert_eq!(contract.case_number_counter, 7);
+        assert_eq!(contract.owner_id, accounts(1));
+        assert_eq!(contract.get_investigation_timeout(), U64(DEFAULT_INVESTIGATION_TIMEOUT_NS));
+        assert_eq!(contract.get_tiers().len(), 3);
+    }
+
+    #[test]
+    fn test_migrate_re_encodes_version_one_cases() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let token_id: TokenId = "1".to_string();
+        let mut investigation_data = UnorderedMap::new(StorageKey::InvestigationData);
+        investigation_data.insert(&token_id, &OldInvestigationMetadata {
+            case_number: 1,
+            target_account: accounts(2),
+            requester: accounts(3),
+            investigation_date: U64(10),
+            status: InvestigationStatus::Completed,
+            summary: Some("Exchange hot wallet".to_string()),
+            transaction_count: 42,
+            total_value_usd: "1000".to_string(),
+            near_balance: "5".to_string(),
+            is_bot: true,
+            last_updated: U64(20),
+        });
+        let old_state = OldState {
+            version: 1,
+            owner_id: accounts(1),
+            tokens: NonFungibleToken::new(
+                StorageKey::NonFungibleToken,
+                accounts(1),
+                Some(StorageKey::TokenMetadata),
+                Some(StorageKey::Enumeration),
+                Some(StorageKey::Approval),
+            ),
+            metadata: LazyOption::new(StorageKey::Metadata, Some(&default_contract_metadata())),
+            case_number_counter: 1,
+            investigated_accounts: LookupMap::new(StorageKey::InvestigatedAccounts),
+            investigation_status: UnorderedMap::new(StorageKey::InvestigationStatus),
+            investigation_data,
+            failed_mints: UnorderedMap::new(StorageKey::FailedMints),
+        };
+        env::state_write(&old_state);
+
+        let contract = Contract::migrate();
+        assert_eq!(contract.investigation_data.len(), 1);
+        let metadata = contract.investigation_data.get(&token_id).unwrap();
+        assert_eq!(metadata.requester, accounts(3));
+        assert_eq!(metadata.status, InvestigationStatus::Completed);
+        assert_eq!(metadata.summary.as_deref(), Some("Exchange hot wallet"));
+        assert_eq!(metadata.transaction_count, 42);
+        assert!(metadata.is_bot);
+        assert_eq!(metadata.last_updated, U64(20));
+        assert_eq!(metadata.visibility, Visibility::Public);
+        assert_eq!(metadata.risk_level, RiskLevel::Unknown);
+    }
+
+    #[test]
+    fn test_consensus_failure_is_not_a_dispute() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let token_id = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        for oracle in [accounts(3), accounts(4)] {
+            testing_env!(get_context(oracle).attached_deposit(DEFAULT_MIN_ORACLE_STAKE).build());
+            contract.register_oracle();
+        }
+        testing_env!(get_context(accounts(1)).build());
+        contract.set_consensus_config(ConsensusConfig {
+            enabled: true,
+            quorum: 2,
+            transaction_count_tolerance_bps: 0,
+        });
+
+        let submit = |contract: &mut Contract, oracle: AccountId, transaction_count: u64| {
+            testing_env!(get_context(oracle).build());
+            let mut metadata = InvestigationMetadata::new(1, accounts(2), accounts(1));
+            metadata.transaction_count = transaction_count;
+            contract.submit_result(token_id.clone(), MetadataUpdate {
+                description: None,
+                extra: serde_json::to_string(&metadata).unwrap(),
+                reference: None,
+                reference_hash: None,
+            })
+        };
+
+        assert_eq!(submit(&mut contract, accounts(3), 100), InvestigationStatus::Processing);
+        assert_eq!(submit(&mut contract, accounts(4), 200), InvestigationStatus::NoConsensus);
+        assert!(contract.get_disputes(token_id.clone()).is_empty());
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.retry_investigation(token_id.clone()).unwrap();
+        assert_eq!(contract.get_investigation_status(token_id.clone()), Some(InvestigationStatus::Pending));
+        assert!(contract.get_oracle_submissions(token_id).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Quorum cannot exceed the number of staked oracles")]
+    fn test_consensus_quorum_needs_enough_oracles() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        testing_env!(get_context(accounts(3)).attached_deposit(DEFAULT_MIN_ORACLE_STAKE).build());
+        contract.register_oracle();
+        testing_env!(get_context(accounts(1)).build());
+        contract.set_consensus_config(ConsensusConfig {
+            enabled: true,
+            quorum: 2,
+            transaction_count_tolerance_bps: 0,
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Attestation nonce has already been used")]
+    fn test_signed_update_is_accepted_once() {
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let token_id = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let public_key = PublicKey::from_parts(
+            CurveType::ED25519,
+            signing_key.verifying_key().to_bytes().to_vec(),
+        ).unwrap();
+        contract.add_attestation_key(public_key.clone());
+
+        let mut metadata = InvestigationMetadata::new(1, accounts(2), accounts(1));
+        metadata.status = InvestigationStatus::Completed;
+        let message = crate::attestation::attestation_message(&token_id, 1, &metadata);
+        let attestation = SignedAttestation {
+            public_key,
+            signature: signing_key.sign(&message).to_bytes().to_vec().into(),
+            nonce: U64(1),
+        };
+        let update = MetadataUpdate {
+            description: None,
+            extra: serde_json::to_string(&metadata).unwrap(),
+            reference: None,
+            reference_hash: None,
+        };
+
+        // Any relayer may submit a correctly signed result
+        testing_env!(get_context(accounts(4)).build());
+        contract.update_investigation_metadata(
+            token_id.clone(),
+            update.clone(),
+            WebhookType::Completion,
+            Some(attestation.clone()),
+        ).unwrap();
+        assert_eq!(contract.get_investigation_status(token_id.clone()), Some(InvestigationStatus::Completed));
+        assert_eq!(contract.get_attestations(token_id.clone()).len(), 1);
+        assert_eq!(contract.get_attestation_nonce(token_id.clone()), U64(1));
+
+        // Replaying the same signed result is rejected
+        testing_env!(get_context(accounts(5)).build());
+        let _ = contract.update_investigation_metadata(token_id, update, WebhookType::Completion, Some(attestation));
+    }
+
+    #[test]
+    fn test_case_artwork_size_is_stable_and_hides_opted_out_targets() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let token_id = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        let mut investigation = contract.get_investigation_metadata(token_id.clone()).unwrap();
+        let pending_svg = render_case_svg(&investigation, false);
+        investigation.status = InvestigationStatus::NoConsensus;
+        investigation.risk_level = RiskLevel::Critical;
+        assert_eq!(render_case_svg(&investigation, false).len(), pending_svg.len());
+        assert_eq!(render_case_svg(&investigation, true).len(), pending_svg.len());
+
+        // Re-rendering for a new status does not grow storage
+        let storage_before = near_sdk::env::storage_usage();
+        contract.internal_refresh_artwork(&token_id, &investigation);
+        assert_eq!(near_sdk::env::storage_usage(), storage_before);
+        let media = contract.get_token_metadata(token_id.clone()).unwrap().media.unwrap();
+
+        testing_env!(get_context(accounts(2)).build());
+        contract.add_opt_out(accounts(2));
+        let investigation = contract.get_investigation_metadata(token_id.clone()).unwrap();
+        let hidden = contract.get_token_metadata(token_id.clone()).unwrap();
+        assert_ne!(hidden.media.clone().unwrap(), media);
+        let svg = render_case_svg(&investigation, true);
+        assert!(!svg.contains(accounts(2).as_str()) && svg.contains("[redacted]"));
+        assert_eq!(hidden.media_hash.unwrap().0, near_sdk::env::sha256(svg.as_bytes()));
+
+        contract.remove_opt_out(accounts(2));
+        let shown = contract.get_token_metadata(token_id).unwrap();
+        assert_eq!(shown.media_hash.unwrap().0, near_sdk::env::sha256(render_case_svg(&investigation, false).as_bytes()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Merged cannot be set through a metadata update")]
+    fn test_analyst_cannot_rewrite_case_identity() {
+        let context = get_context(accounts(4));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        contract.register_analyst("Sleuth".to_string(), "On-chain forensics".to_string(), NearToken::from_millinear(50));
+
+        testing_env!(get_context(accounts(3)).attached_deposit(NearToken::from_millinear(60).saturating_add(MINT_STORAGE_COST)).build());
+        let options = InvestigationOptions {
+            analyst: Some(accounts(4)),
+            ..Default::default()
+        };
+        let token_id = contract.start_investigation(accounts(2), Some(options)).unwrap().request_id;
+
+        // The analyst names itself as requester and retargets the case
+        testing_env!(get_context(accounts(4)).build());
+        let mut metadata = InvestigationMetadata::new(9, accounts(5), accounts(4));
+        metadata.status = InvestigationStatus::Processing;
+        let update = |metadata: &InvestigationMetadata| MetadataUpdate {
+            description: None,
+            extra: serde_json::to_string(metadata).unwrap(),
+            reference: None,
+            reference_hash: None,
+        };
+        contract.update_investigation_metadata(token_id.clone(), update(&metadata), WebhookType::Progress, None).unwrap();
+        let stored = contract.get_investigation_metadata(token_id.clone()).unwrap();
+        assert_eq!((stored.case_number, stored.target_account, stored.requester), (1, accounts(2), accounts(3)));
+
+        metadata.status = InvestigationStatus::Merged;
+        let _ = contract.update_investigation_metadata(token_id, update(&metadata), WebhookType::Progress, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Analysts can only report Processing or Completed results")]
+    fn test_analyst_cannot_fail_a_case() {
+        let context = get_context(accounts(4));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        contract.register_analyst("Sleuth".to_string(), "On-chain forensics".to_string(), NearToken::from_millinear(50));
+
+        testing_env!(get_context(accounts(3)).attached_deposit(NearToken::from_millinear(60).saturating_add(MINT_STORAGE_COST)).build());
+        let options = InvestigationOptions {
+            analyst: Some(accounts(4)),
+            ..Default::default()
+        };
+        let token_id = contract.start_investigation(accounts(2), Some(options)).unwrap().request_id;
+
+        testing_env!(get_context(accounts(4)).build());
+        let mut metadata = InvestigationMetadata::new(1, accounts(2), accounts(3));
+        metadata.status = InvestigationStatus::Failed;
+        let _ = contract.update_investigation_metadata(token_id, MetadataUpdate {
+            description: None,
+            extra: serde_json::to_string(&metadata).unwrap(),
+            reference: None,
+            reference_hash: None,
+        }, WebhookType::Error, None);
+    }
+
+    #[test]
+    fn test_monitoring_run_pays_for_its_case() {
+        let mut context = get_context(accounts(3));
+        testing_env!(context.build());
+        let mut contract = Contract::new(accounts(1), None);
+        context.attached_deposit(contract.get_monitoring_fee());
+        testing_env!(context.build());
+        let subscription = contract.subscribe_monitoring(accounts(2), U64(MIN_MONITORING_INTERVAL_NS), 1);
+        assert!(subscription.fee_per_period > INVESTIGATION_FEE);
+
+        context.block_timestamp(MIN_MONITORING_INTERVAL_NS);
+        testing_env!(context.build());
+        let storage_before = env::storage_usage();
+        contract.tick_monitoring(None);
+        let storage_cost = env::storage_byte_cost()
+            .saturating_mul((env::storage_usage() - storage_before) as u128);
+        let token_id = contract.investigated_accounts.get(&accounts(2)).unwrap();
+        assert_eq!(contract.case_payments.get(&token_id), Some(INVESTIGATION_FEE));
+        assert_eq!(contract.case_payers.get(&token_id), Some(accounts(3)));
+        assert!(storage_cost <= subscription.storage_per_period);
+        // Whatever storage the run did not use is kept for the owner
+        assert!(!contract.get_collected_monitoring_fees().is_zero());
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.withdraw_monitoring_fees(None);
+        assert!(contract.get_collected_monitoring_fees().is_zero());
+    }
+
+    #[test]
+    fn test_stale_monitoring_run_refunds_its_subscriber() {
+        testing_env!(get_context(accounts(1)).build());
+        let mut contract = Contract::new(accounts(1), None);
+        let token_id = complete_bounty_target(&mut contract);
+        // The original run delivered its result, so its fee is no longer refundable
+        assert!(contract.case_payments.get(&token_id).is_none());
+
+        let mut context = get_context(accounts(3));
+        context.attached_deposit(contract.get_monitoring_fee());
+        testing_env!(context.build());
+        contract.subscribe_monitoring(accounts(2), U64(MIN_MONITORING_INTERVAL_NS), 1);
+        context.block_timestamp(MIN_MONITORING_INTERVAL_NS);
+        testing_env!(context.build());
+        contract.tick_monitoring(None);
+
+        testing_env!(get_context(accounts(4))
+            .block_timestamp(MIN_MONITORING_INTERVAL_NS + DEFAULT_INVESTIGATION_TIMEOUT_NS)
+            .build());
+        contract.expire_stale_investigation(token_id.clone());
+        let receipts = near_sdk::test_utils::get_created_receipts();
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].receiver_id, accounts(3));
+        assert!(contract.case_payments.get(&token_id).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot record edges for a private investigation")]
+    fn test_private_investigation_keeps_edges_off_the_graph() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let options = InvestigationOptions {
+            visibility: Visibility::Private,
+            encryption_public_key: Some(vec![9u8; 32].into()),
+            ..Default::default()
+        };
+        let token_id = contract.start_investigation(accounts(2), Some(options)).unwrap().request_id;
+        contract.record_edges(token_id, vec![EdgeInput {
+            from_account: accounts(2),
+            to_account: accounts(3),
+            relation_kind: RelationKind::Transfer,
+            weight: 1,
+        }]);
+    }
+
+    #[test]
+    fn test_archive_refunds_the_recorded_payer() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let options = InvestigationOptions {
+            retention_period_ns: Some(U64(1_000)),
+            ..Default::default()
+        };
+        let token_id = contract.start_investigation(accounts(2), Some(options)).unwrap().request_id;
+        testing_env!(get_context(accounts(3)).attached_deposit(DEFAULT_MIN_ORACLE_STAKE).build());
+        contract.register_oracle();
+        testing_env!(get_context(accounts(3)).block_timestamp(DEFAULT_INVESTIGATION_TIMEOUT_NS).build());
+        contract.expire_stale_investigation(token_id.clone());
+        testing_env!(get_context(accounts(3))
+            .attached_deposit(INVESTIGATION_FEE)
+            .block_timestamp(DEFAULT_INVESTIGATION_TIMEOUT_NS)
+            .build());
+        assert!(contract.retry_investigation(token_id.clone()).is_ok());
+
+        testing_env!(get_context(accounts(3)).block_timestamp(DEFAULT_INVESTIGATION_TIMEOUT_NS).build());
+        contract.archive_investigation(token_id);
+        let receipts = near_sdk::test_utils::get_created_receipts();
+        assert!(!receipts.is_empty());
+        assert!(receipts.iter().all(|receipt| receipt.receiver_id == accounts(3)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the token owner or payer can archive a case")]
+    fn test_archive_restricted_to_owner_or_payer() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let options = InvestigationOptions {
+            retention_period_ns: Some(U64(1_000)),
+            ..Default::default()
+        };
+        let token_id = contract.start_investigation(accounts(2), Some(options)).unwrap().request_id;
+        testing_env!(get_context(accounts(3)).block_timestamp(1_000).build());
+        contract.archive_investigation(token_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "The analyst or resolvers cannot rate their own case")]
+    fn test_resolver_cannot_rate_own_case() {
+        testing_env!(get_context(accounts(5)).attached_deposit(DEFAULT_MIN_ORACLE_STAKE).build());
+        let mut contract = Contract::new(accounts(1), None);
+        contract.register_oracle();
+
+        testing_env!(get_context(accounts(5)).build());
+        let token_id = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        let mut metadata = InvestigationMetadata::new(1, accounts(2), accounts(5));
+        metadata.status = InvestigationStatus::Completed;
+        let _ = contract.update_investigation_metadata(
+            token_id.clone(),
+            MetadataUpdate {
+                description: None,
+                extra: serde_json::to_string(&metadata).unwrap(),
+                reference: None,
+                reference_hash: None,
+            },
+            WebhookType::Completion,
+            None,
+        );
+        contract.rate_investigation(token_id, 5, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Attestation key already registered")]
+    fn test_revoked_attestation_key_cannot_be_re_added() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let public_key: PublicKey = "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp".parse().unwrap();
+        contract.add_attestation_key(public_key.clone());
+        contract.revoke_attestation_key(public_key.clone());
+        contract.add_attestation_key(public_key);
+    }
+
+    #[test]
+    fn test_attestation_history_is_capped() {
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(1), None);
+        let token_id = contract.start_investigation(accounts(2), None).unwrap().request_id;
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let public_key = PublicKey::from_parts(
+            CurveType::ED25519,
+            signing_key.verifying_key().to_bytes().to_vec(),
+        ).unwrap();
+        contract.add_attestation_key(public_key.clone());
+
+        let mut metadata = InvestigationMetadata::new(1, accounts(2), accounts(1));
+        metadata.status = InvestigationStatus::Processing;
+        let update = MetadataUpdate {
+            description: None,
+            extra: serde_json::to_string(&metadata).unwrap(),
+            reference: None,
+            reference_hash: None,
+        };
+        for nonce in 1..=(MAX_ATTESTATIONS_PER_CASE as u64 + 1) {
+            let message = crate::attestation::attestation_message(&token_id, nonce, &metadata);
+            let attestation = SignedAttestation {
+                public_key: public_key.clone(),
+                signature: signing_key.sign(&message).to_bytes().to_vec().into(),
+                nonce: U64(nonce),
+            };
+            contract.update_investigation_metadata(
+                token_id.clone(),
+                update.clone(),
+                WebhookType::Progress,
+                Some(attestation),
+            ).unwrap();
+        }
+
+        let attestations = contract.get_attestations(token_id.clone());
+        assert_eq!(attestations.len(), MAX_ATTESTATIONS_PER_CASE);
+        assert_eq!(attestations[0].nonce, U64(2));
+        assert_eq!(contract.get_attestation_nonce(token_id), U64(MAX_ATTESTATIONS_PER_CASE as u64 + 1));
+    }
+}
@@ -0,0 +1,67 @@
+use std::prelude::v1::*;
+use crate::*;
+use near_sdk::serde::{Deserialize, Serialize};
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TierConfig {
+    pub tier: InvestigationTier,
+    pub price: NearToken,
+    /// Promised turnaround, in nanoseconds
+    pub sla_ns: U64,
+    /// How many hops of counterparties the pipeline analyses
+    pub analysis_depth: u8,
+}
+
+pub fn default_tiers() -> Vec<TierConfig> {
+    vec![
+        TierConfig {
+            tier: InvestigationTier::Standard,
+            price: INVESTIGATION_FEE,
+            sla_ns: U64(24 * 60 * 60 * 1_000_000_000),
+            analysis_depth: 1,
+        },
+        TierConfig {
+            tier: InvestigationTier::Priority,
+            price: NearToken::from_millinear(100),
+            sla_ns: U64(60 * 60 * 1_000_000_000),
+            analysis_depth: 1,
+        },
+        TierConfig {
+            tier: InvestigationTier::DeepDive,
+            price: NearToken::from_millinear(500),
+            sla_ns: U64(72 * 60 * 60 * 1_000_000_000),
+            analysis_depth: 2,
+        },
+    ]
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_tiers(&self) -> Vec<TierConfig> {
+        self.tiers.clone()
+    }
+
+    pub fn set_tier_config(&mut self, config: TierConfig) {
+        self.assert_owner();
+        require!(config.price >= INVESTIGATION_FEE, "Tier price must cover the base investigation fee");
+        match self.tiers.iter_mut().find(|existing| existing.tier == config.tier) {
+            Some(existing) => *existing = config,
+            None => self.tiers.push(config),
+        }
+    }
+
+    pub fn get_case_tier(&self, token_id: TokenId) -> InvestigationTier {
+        self.case_tiers.get(&token_id).unwrap_or_default()
+    }
+}
+
+impl Contract {
+    pub(crate) fn tier_config(&self, tier: &InvestigationTier) -> TierConfig {
+        self.tiers
+            .iter()
+            .find(|config| &config.tier == tier)
+            .cloned()
+            .unwrap_or_else(|| env::panic_str("Tier is not configured"))
+    }
+}
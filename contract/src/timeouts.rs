@@ -0,0 +1,148 @@
+use std::prelude::v1::*;
+use crate::*;
+
+/// 24 hours
+pub const DEFAULT_INVESTIGATION_TIMEOUT_NS: u64 = 24 * 60 * 60 * 1_000_000_000;
+const MAX_STALE_SCAN: u64 = 100;
+
+#[near_bindgen]
+impl Contract {
+    /// Timeout for cases recorded before tiers existed; tiered cases use their tier's SLA
+    pub fn set_investigation_timeout(&mut self, timeout_ns: U64) {
+        self.assert_owner();
+        require!(timeout_ns.0 > 0, "Timeout must be positive");
+        self.investigation_timeout_ns = timeout_ns;
+    }
+
+    pub fn get_investigation_timeout(&self) -> U64 {
+        self.investigation_timeout_ns
+    }
+
+    /// Fails a case whose pipeline has not reported back within the SLA and refunds
+    /// the requester. Callable by anyone; the case can then be `retry_investigation`-ed.
+    pub fn expire_stale_investigation(&mut self, token_id: TokenId) -> InvestigationStatus {
+        require!(self.is_stale(&token_id), "Investigation is not stale");
+        let sla_ns = self.case_sla_ns(&token_id);
+        self.internal_set_status(&token_id, InvestigationStatus::Failed);
+        self.internal_refund_case(&token_id);
+
+        InvestigationEvent::Failed {
+            token_id,
+            error: format!(
+                "Timed out after {}ns without a pipeline update",
+                sla_ns
+            ),
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+
+        InvestigationStatus::Failed
+    }
+
+    /// Scans `limit` cases starting at `from_index` and returns the stale ones,
+    /// so a page may hold fewer entries than `limit`
+    pub fn get_stale_investigations(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<TokenId> {
+        let limit = limit.unwrap_or(MAX_STALE_SCAN).min(MAX_STALE_SCAN) as usize;
+        self.investigation_status
+            .keys()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit)
+            .filter(|token_id| self.is_stale(token_id))
+            .collect()
+    }
+}
+
+impl Contract {
+    pub(crate) fn is_stale(&self, token_id: &TokenId) -> bool {
+        let status = match self.investigation_status.get(token_id) {
+            Some(status) => status,
+            None => return false,
+        };
+        if status != InvestigationStatus::Pending && status != InvestigationStatus::Processing {
+            return false;
+        }
+        self.get_investigation_metadata(token_id.clone()).is_some_and(|metadata| {
+            env::block_timestamp().saturating_sub(metadata.last_updated.0) >= self.case_sla_ns(token_id)
+        })
+    }
+
+    /// The SLA promised by the case's tier
+    pub(crate) fn case_sla_ns(&self, token_id: &TokenId) -> u64 {
+        match self.case_tiers.get(token_id) {
+            Some(tier) => self.tier_config(&tier).sla_ns.0,
+            None => self.investigation_timeout_ns.0,
+        }
+    }
+
+    pub(crate) fn internal_record_payment(&mut self, token_id: &TokenId, payer: &AccountId, amount: NearToken) {
+        self.case_payments.insert(token_id, &amount);
+        self.case_payers.insert(token_id, payer);
+    }
+
+    /// Marks the current run's fee as earned once its result is in, so a later stale
+    /// re-run can only ever refund what was paid for that re-run
+    pub(crate) fn internal_settle_case(&mut self, token_id: &TokenId) {
+        self.case_payments.remove(token_id);
+    }
+
+    /// Returns whatever was paid for the current run to whoever paid it, at most once
+    pub(crate) fn internal_refund_case(&mut self, token_id: &TokenId) -> NearToken {
+        let escrow = self.internal_refund_analyst_escrow(token_id);
+        let payer = self.case_payers.get(token_id)
+            .or_else(|| self.get_investigation_metadata(token_id.clone()).map(|investigation| investigation.requester));
+        match (self.case_payments.remove(token_id), payer) {
+            (Some(amount), Some(payer)) if !amount.is_zero() => {
+                Promise::new(payer).transfer(amount);
+                amount.saturating_add(escrow)
+            }
+            _ => escrow,
+        }
+    }
+
+    /// Re-queues a failed or unresolved case that still has its token, charging the fee again.
+    /// Only the requester or an oracle may retry; any excess deposit is refunded.
+    pub(crate) fn internal_retry_failed_case(&mut self, token_id: TokenId) {
+        require!(
+            self.investigation_status.get(&token_id).is_some_and(|status| status.is_retryable()),
+            "Only failed investigations can be retried"
+        );
+        let caller = env::predecessor_account_id();
+        let investigation = self.get_investigation_metadata(token_id.clone())
+            .unwrap_or_else(|| env::panic_str("Investigation not found"));
+        require!(
+            caller == investigation.requester || self.is_oracle(&caller),
+            "Only the requester or an oracle can retry an investigation"
+        );
+        let price = self.tier_config(&self.case_tiers.get(&token_id).unwrap_or_default()).price;
+        let deposit = env::attached_deposit();
+        require!(
+            deposit >= price,
+            format!("Must attach at least {} to retry", price.exact_amount_display())
+        );
+        self.internal_reopen_case(&token_id, &caller, price);
+        if deposit > price {
+            Promise::new(caller).transfer(deposit.saturating_sub(price));
+        }
+    }
+
+    /// Puts a failed or stale case back in the queue with a fresh `payment` from `payer`.
+    /// A stale run is refunded first so the case never holds two payments.
+    pub(crate) fn internal_reopen_case(&mut self, token_id: &TokenId, payer: &AccountId, payment: NearToken) -> u32 {
+        if self.is_stale(token_id) {
+            self.internal_refund_case(token_id);
+        }
+        self.internal_record_payment(token_id, payer, payment);
+        // A new run collects a fresh round of oracle results
+        self.oracle_submissions.remove(token_id);
+
+        let attempt = self.retry_counts.get(token_id).unwrap_or(0) + 1;
+        self.retry_counts.insert(token_id, &attempt);
+        self.internal_set_status(token_id, InvestigationStatus::Pending);
+
+        InvestigationEvent::RetryAttempted {
+            token_id: token_id.clone(),
+            attempt,
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+        attempt
+    }
+}
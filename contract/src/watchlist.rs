@@ -0,0 +1,100 @@
+use std::prelude::v1::*;
+use crate::*;
+
+pub const MAX_WATCHLIST_SIZE: usize = 100;
+/// Bounds the subscriber list carried in a single notification event
+pub const MAX_WATCHERS_PER_ACCOUNT: usize = 100;
+
+#[near_bindgen]
+impl Contract {
+    /// Follows `account_id`; the caller pays for the storage used
+    #[payable]
+    pub fn add_to_watchlist(&mut self, account_id: AccountId) {
+        let user = env::predecessor_account_id();
+        let initial_storage_usage = env::storage_usage();
+
+        let mut watchlist = self.watchlists.get(&user).unwrap_or_default();
+        require!(!watchlist.contains(&account_id), "Account is already on the watchlist");
+        require!(
+            watchlist.len() < MAX_WATCHLIST_SIZE,
+            format!("Watchlist cannot exceed {} accounts", MAX_WATCHLIST_SIZE)
+        );
+        let mut watchers = self.watchers.get(&account_id).unwrap_or_default();
+        require!(
+            watchers.len() < MAX_WATCHERS_PER_ACCOUNT,
+            "Account has reached the maximum number of watchers"
+        );
+
+        watchlist.push(account_id.clone());
+        watchers.push(user.clone());
+        self.watchlists.insert(&user, &watchlist);
+        self.watchers.insert(&account_id, &watchers);
+
+        let storage_cost = env::storage_byte_cost()
+            .saturating_mul(env::storage_usage().saturating_sub(initial_storage_usage) as u128);
+        let deposit = env::attached_deposit();
+        require!(
+            deposit >= storage_cost,
+            format!("Must attach at least {} for storage", storage_cost.exact_amount_display())
+        );
+        if deposit > storage_cost {
+            Promise::new(user).transfer(deposit.saturating_sub(storage_cost));
+        }
+    }
+
+    /// Unfollows `account_id` and refunds the storage freed
+    pub fn remove_from_watchlist(&mut self, account_id: AccountId) {
+        let user = env::predecessor_account_id();
+        let initial_storage_usage = env::storage_usage();
+
+        let mut watchlist = self.watchlists.get(&user).unwrap_or_default();
+        let position = watchlist.iter().position(|watched| watched == &account_id)
+            .unwrap_or_else(|| env::panic_str("Account is not on the watchlist"));
+        watchlist.swap_remove(position);
+        if watchlist.is_empty() {
+            self.watchlists.remove(&user);
+        } else {
+            self.watchlists.insert(&user, &watchlist);
+        }
+
+        let mut watchers = self.watchers.get(&account_id).unwrap_or_default();
+        watchers.retain(|watcher| watcher != &user);
+        if watchers.is_empty() {
+            self.watchers.remove(&account_id);
+        } else {
+            self.watchers.insert(&account_id, &watchers);
+        }
+
+        let refund = env::storage_byte_cost()
+            .saturating_mul(initial_storage_usage.saturating_sub(env::storage_usage()) as u128);
+        if !refund.is_zero() {
+            Promise::new(user).transfer(refund);
+        }
+    }
+
+    pub fn get_watchlist(&self, account_id: AccountId) -> Vec<AccountId> {
+        self.watchlists.get(&account_id).unwrap_or_default()
+    }
+
+    pub fn get_watchers(&self, account_id: AccountId) -> Vec<AccountId> {
+        self.watchers.get(&account_id).unwrap_or_default()
+    }
+}
+
+impl Contract {
+    /// Emits a notification listing everyone watching the case's target
+    pub(crate) fn internal_notify_watchers(&self, token_id: &TokenId, metadata: &InvestigationMetadata) {
+        let watchers = self.watchers.get(&metadata.target_account).unwrap_or_default();
+        if watchers.is_empty() {
+            return;
+        }
+        InvestigationEvent::WatchlistNotification {
+            token_id: token_id.clone(),
+            target_account: metadata.target_account.to_string(),
+            status: format!("{:?}", metadata.status),
+            risk_level: format!("{:?}", metadata.risk_level),
+            subscribers: watchers.iter().map(|watcher| watcher.to_string()).collect(),
+            timestamp: U64(env::block_timestamp()),
+        }.log();
+    }
+}
@@ -23,6 +23,7 @@ impl WebhookType {
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "progress" => Some(Self::Progress),